@@ -11,6 +11,7 @@ use walkdir::WalkDir;
 
 fn main() {
     compile_shaders().unwrap();
+    generate_header().unwrap();
 }
 
 fn compile_shaders() -> Result<(), Box<dyn Error>> {
@@ -53,6 +54,43 @@ fn compile_shaders() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+fn generate_header() -> Result<(), Box<dyn Error>> {
+    let sources = ["src/ffi.rs", "src/float_range.rs", "src/seam.rs"];
+    for src_path in sources {
+        println!("cargo:rerun-if-changed={}", src_path);
+    }
+
+    let config = cbindgen::Config {
+        language: cbindgen::Language::C,
+        include_guard: Some("SM64_SEAM_TOOL_H".to_owned()),
+        autogen_warning: Some(
+            "/* Generated by build.rs from src/ffi.rs. Do not edit by hand. */".to_owned(),
+        ),
+        cpp_compat: true,
+        usize_is_size_t: true,
+        enumeration: cbindgen::EnumConfig {
+            rename_variants: cbindgen::RenameRule::QualifiedScreamingSnakeCase,
+            ..Default::default()
+        },
+        // Functions take the filter as a uint32_t, so it isn't referenced directly
+        export: cbindgen::ExportConfig {
+            include: vec!["SeamPointFilter".to_owned()],
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let mut builder = cbindgen::Builder::new().with_config(config);
+    for src_path in sources {
+        builder = builder.with_src(src_path);
+    }
+    builder
+        .generate()?
+        .write_to_file("include/sm64_seam_tool.h");
+
+    Ok(())
+}
+
 fn shader_files() -> Result<Vec<PathBuf>, Box<dyn Error>> {
     let index_filename = "shaders/index.txt";
     println!("cargo:rerun-if-changed={}", index_filename);
@@ -0,0 +1,149 @@
+#ifndef SM64_SEAM_TOOL_H
+#define SM64_SEAM_TOOL_H
+
+/* Generated by build.rs from src/ffi.rs. Do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * See `PointStatus`.
+ */
+typedef enum SeamPointStatus {
+  SEAM_POINT_STATUS_GAP,
+  SEAM_POINT_STATUS_OVERLAP,
+  SEAM_POINT_STATUS_NONE,
+} SeamPointStatus;
+
+/**
+ * See `PointFilter`. Filters defined by scripts are not available through the C API.
+ *
+ * Functions take the filter as a `uint32_t` so that invalid values from C can be rejected.
+ */
+typedef enum SeamPointFilter {
+  SEAM_POINT_FILTER_NONE,
+  SEAM_POINT_FILTER_INT_Y,
+  SEAM_POINT_FILTER_QUARTER_INT_Y,
+} SeamPointFilter;
+
+typedef struct Seam Seam;
+
+/**
+ * A closed range of float values.
+ */
+typedef struct RangeF32 {
+  float start;
+  float end;
+} RangeF32;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Find the seam between an edge of one wall and an edge of another.
+ *
+ * `vertices1` and `vertices2` each point to 6 values: the two endpoints of the edge, in the
+ * wall's vertex order. `normal1` and `normal2` each point to 3 values.
+ *
+ * Returns null if the edges do not form a seam. The result must be freed with `seam_free`.
+ *
+ * # Safety
+ *
+ * All pointers must be valid for reads of the given number of values.
+ */
+struct Seam *seam_between(const int16_t *vertices1,
+                          const float *normal1,
+                          const int16_t *vertices2,
+                          const float *normal2);
+
+/**
+ * Free a seam returned by `seam_between`. Does nothing if `seam` is null.
+ *
+ * # Safety
+ *
+ * `seam` must be null or a pointer returned by `seam_between` that has not already been freed.
+ */
+void seam_free(struct Seam *seam);
+
+/**
+ * The range of w values covered by both edges of the seam.
+ *
+ * # Safety
+ *
+ * `seam` must be a valid pointer returned by `seam_between`.
+ */
+struct RangeF32 seam_w_range(const struct Seam *seam);
+
+/**
+ * Classify the point on the seam with the given w value, and write the result to `status_out`.
+ *
+ * `filter` is a `SeamPointFilter` value. Returns false without writing anything if `seam` or
+ * `status_out` is null or `filter` is invalid.
+ *
+ * The y value of the gap or overlap (or an approximate y value on the seam if there is neither)
+ * is written to `y_out` if it is non-null.
+ *
+ * # Safety
+ *
+ * `seam` must be null or a valid pointer returned by `seam_between`, and `status_out` and `y_out`
+ * must be null or valid for writes.
+ */
+bool seam_check_point(const struct Seam *seam,
+                      float w,
+                      uint32_t filter,
+                      enum SeamPointStatus *status_out,
+                      float *y_out);
+
+/**
+ * Return true if the projected point `(w, y)` passes the filter.
+ *
+ * `filter` is a `SeamPointFilter` value. Returns false if it is invalid.
+ */
+bool seam_point_filter_matches(uint32_t filter, float w, float y);
+
+/**
+ * The range of floats from `min` to `max`, inclusive.
+ */
+struct RangeF32 range_f32_inclusive(float min, float max);
+
+/**
+ * The number of floats in the range.
+ */
+size_t range_f32_count(struct RangeF32 range);
+
+/**
+ * Pop the smallest float from the range and write it to `out`.
+ *
+ * Returns false if the range is empty. Used to iterate over every float in a range:
+ *
+ * ```c
+ * RangeF32 range = seam_w_range(seam);
+ * float w;
+ * while (range_f32_next(&range, &w)) { ... }
+ * ```
+ *
+ * # Safety
+ *
+ * `range` must be valid for reads and writes, and `out` must be valid for writes.
+ */
+bool range_f32_next(struct RangeF32 *range, float *out);
+
+/**
+ * The next float larger than `x`, treating denormals as zero.
+ */
+float f32_next(float x);
+
+/**
+ * The previous float smaller than `x`, treating denormals as zero.
+ */
+float f32_prev(float x);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* SM64_SEAM_TOOL_H */
@@ -0,0 +1,84 @@
+use crate::seam::Seam;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    io::{self, BufWriter},
+    path::Path,
+};
+
+pub const BOOKMARKS_FILE: &str = "bookmarks.json";
+
+/// A named point on a seam.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub name: String,
+    pub comment: String,
+    pub w: f32,
+    pub y: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeamBookmarks {
+    pub seam: Seam,
+    pub bookmarks: Vec<Bookmark>,
+}
+
+/// All bookmarks, grouped by seam.
+///
+/// Bookmarks are keyed by the seam's geometry rather than by level, so a bookmark shows up
+/// whenever its seam is loaded.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Bookmarks {
+    pub seams: Vec<SeamBookmarks>,
+}
+
+impl Bookmarks {
+    /// Load bookmarks from a file, or return no bookmarks if the file doesn't exist.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(text) => Ok(serde_json::from_str(&text)?),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => Err(error),
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        // Write to a temporary file first so that a failed save can't leave a partial file
+        let path = path.as_ref();
+        let mut temp_path = path.as_os_str().to_owned();
+        temp_path.push(".tmp");
+
+        let mut writer = BufWriter::new(fs::File::create(&temp_path)?);
+        serde_json::to_writer_pretty(&mut writer, self)?;
+        writer
+            .into_inner()
+            .map_err(io::IntoInnerError::into_error)?
+            .sync_all()?;
+        fs::rename(&temp_path, path)
+    }
+
+    pub fn for_seam(&self, seam: &Seam) -> &[Bookmark] {
+        self.seams
+            .iter()
+            .find(|entry| &entry.seam == seam)
+            .map(|entry| entry.bookmarks.as_slice())
+            .unwrap_or_default()
+    }
+
+    pub fn add(&mut self, seam: &Seam, bookmark: Bookmark) {
+        match self.seams.iter_mut().find(|entry| &entry.seam == seam) {
+            Some(entry) => entry.bookmarks.push(bookmark),
+            None => self.seams.push(SeamBookmarks {
+                seam: seam.clone(),
+                bookmarks: vec![bookmark],
+            }),
+        }
+    }
+
+    pub fn remove(&mut self, seam: &Seam, index: usize) {
+        if let Some(entry) = self.seams.iter_mut().find(|entry| &entry.seam == seam) {
+            entry.bookmarks.remove(index);
+        }
+        self.seams.retain(|entry| !entry.bookmarks.is_empty());
+    }
+}
@@ -0,0 +1,74 @@
+use crate::{
+    game_state::{Globals, Surface},
+    logging::LOG_FILE,
+    settings::Settings,
+};
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    time::{SystemTime, UNIX_EPOCH},
+};
+use zip::{ZipWriter, write::SimpleFileOptions};
+
+pub const CONFIG_FILE: &str = "config.json";
+
+/// Write a zip file containing everything needed to reproduce a bug report, and return its
+/// filename.
+///
+/// The bundle contains config.json, the current settings, the log, version info, and a dump of the
+/// surface pool (both the raw bytes and the parsed surfaces).
+pub fn create_diagnostic_bundle(
+    settings: &Settings,
+    globals: &Globals,
+    surface_pool: &[u8],
+    surfaces: &[Surface],
+) -> io::Result<String> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let filename = format!("diagnostics-{}.zip", now);
+
+    // Make sure the log file includes everything logged so far
+    log::logger().flush();
+
+    let mut zip = ZipWriter::new(BufWriter::new(File::create(&filename)?));
+    let mut add_file = |name: &str, contents: &[u8]| -> io::Result<()> {
+        zip.start_file(name, SimpleFileOptions::default())?;
+        zip.write_all(contents)
+    };
+    for name in [CONFIG_FILE, LOG_FILE] {
+        match fs::read(name) {
+            Ok(contents) => add_file(name, &contents)?,
+            Err(error) => log::warn!("Diagnostic bundle is missing {}: {}", name, error),
+        }
+    }
+    add_file("settings.json", &serde_json::to_vec_pretty(settings)?)?;
+    add_file("version.txt", version_info(settings, globals).as_bytes())?;
+    add_file("surface_pool.bin", surface_pool)?;
+    add_file("surfaces.json", &serde_json::to_vec_pretty(surfaces)?)?;
+    zip.finish()?.flush()?;
+
+    Ok(filename)
+}
+
+fn version_info(settings: &Settings, globals: &Globals) -> String {
+    format!(
+        "sm64-seam-tool {}\n\
+         os: {} ({})\n\
+         process: {}\n\
+         base address: {}\n\
+         game version: {}\n\
+         globals: {:#x?}\n",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        settings.process_name.as_deref().unwrap_or("unknown"),
+        settings
+            .base_address
+            .map(|addr| format!("{:#X}", addr))
+            .unwrap_or_else(|| "unknown".to_owned()),
+        settings.game_version.as_deref().unwrap_or("unknown"),
+        globals,
+    )
+}
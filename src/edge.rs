@@ -1,8 +1,10 @@
 use crate::float_range::{RangeF32, flush_f32_to_zero};
+use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display};
 
 /// The axis along which a wall projects.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ProjectionAxis {
     X,
     Z,
@@ -32,7 +34,8 @@ impl Display for ProjectionAxis {
 ///
 /// An x projective surface is positive iff `normal.x > 0`.
 /// A z projective surfaces is positive iff `normal.z <= 0`.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Orientation {
     /// Accept r if r >= 0.
     Positive,
@@ -63,7 +66,7 @@ impl Orientation {
 }
 
 /// A projected point used for edge calculations.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct ProjectedPoint<T> {
     /// The relevant non-y coordinate.
     ///
@@ -92,7 +95,7 @@ impl<T: Clone> ProjectedPoint<T> {
 /// An edge of a wall.
 ///
 /// `vertex1`, `vertex2` should be listed in CCW order (i.e. match the game's order).
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Edge {
     pub projection_axis: ProjectionAxis,
     pub orientation: Orientation,
@@ -151,6 +154,16 @@ impl Edge {
 
     /// Return true if the projected point lies on the inside of the edge.
     pub fn accepts_projected(&self, point: ProjectedPoint<f32>) -> bool {
+        let r = self.cross_product(point);
+        match self.orientation {
+            Orientation::Positive => r >= 0.0,
+            Orientation::Negative => r <= 0.0,
+        }
+    }
+
+    /// The value `r` that the game compares against zero to determine which side of the edge the
+    /// point lies on.
+    pub fn cross_product(&self, point: ProjectedPoint<f32>) -> f32 {
         let w = flush_f32_to_zero(point.w);
         let y = flush_f32_to_zero(point.y);
 
@@ -160,13 +173,8 @@ impl Edge {
         let w2 = self.vertex2.w as f32;
         let y2 = self.vertex2.y as f32;
 
-        let r = flush_f32_to_zero(
+        flush_f32_to_zero(
             flush_f32_to_zero((y1 - y) * (w2 - w1)) - flush_f32_to_zero((w1 - w) * (y2 - y1)),
-        );
-
-        match self.orientation {
-            Orientation::Positive => r >= 0.0,
-            Orientation::Negative => r <= 0.0,
-        }
+        )
     }
 }
@@ -0,0 +1,1222 @@
+use crate::{
+    edge::ProjectionAxis,
+    float_range::{RangeF32, next_f32},
+    seam::{PointFilter, PointStatus, PointStatusFilter, Seam},
+};
+use flate2::{Compression as GzCompression, bufread::MultiGzDecoder, write::GzEncoder};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::{self, Display},
+    fs::{self, File, OpenOptions},
+    io::{self, BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write},
+    path::{self, Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant},
+};
+use sysinfo::Disks;
+
+const RUNS_MAGIC: &[u8; 8] = b"SEAMRUNS";
+const RUNS_VERSION: u8 = 1;
+const GZIP_MAGIC: &[u8] = &[0x1F, 0x8B];
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xB5, 0x2F, 0xFD];
+const ESTIMATE_SAMPLES: usize = 256;
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(10);
+/// The number of rows between progress updates when reading a CSV.
+const PROGRESS_INTERVAL: usize = 10_000;
+
+#[derive(Debug)]
+pub struct ExportProgress {
+    pub complete: usize,
+    pub total: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    /// One CSV row per float.
+    Points,
+    /// One line per float containing only the position, for pasting into STROOP or a memory
+    /// editor.
+    Positions,
+    RunsCsv,
+    RunsBinary,
+}
+
+impl ExportFormat {
+    pub fn all() -> Vec<Self> {
+        vec![
+            Self::Points,
+            Self::Positions,
+            Self::RunsCsv,
+            Self::RunsBinary,
+        ]
+    }
+
+    pub fn run_format(&self) -> Option<RunFormat> {
+        match self {
+            ExportFormat::Points | ExportFormat::Positions => None,
+            ExportFormat::RunsCsv => Some(RunFormat::Csv),
+            ExportFormat::RunsBinary => Some(RunFormat::Binary),
+        }
+    }
+}
+
+impl Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportFormat::Points => write!(f, "points (csv)"),
+            ExportFormat::Positions => write!(f, "positions (stroop)"),
+            ExportFormat::RunsCsv => write!(f, "runs (csv)"),
+            ExportFormat::RunsBinary => write!(f, "runs (binary)"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RunFormat {
+    Csv,
+    Binary,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Compression {
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    pub fn all() -> Vec<Self> {
+        vec![Self::None, Self::Gzip, Self::Zstd]
+    }
+}
+
+impl Display for Compression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Compression::None => write!(f, "no compression"),
+            Compression::Gzip => write!(f, "gzip"),
+            Compression::Zstd => write!(f, "zstd"),
+        }
+    }
+}
+
+/// A file writer that optionally compresses its output.
+///
+/// `finish` must be called to flush the compressed stream.
+pub enum ExportWriter {
+    Plain(BufWriter<File>),
+    Gzip(GzEncoder<BufWriter<File>>),
+    Zstd(zstd::Encoder<'static, BufWriter<File>>),
+}
+
+impl ExportWriter {
+    pub fn create(path: impl AsRef<Path>, compression: Compression) -> io::Result<Self> {
+        let writer = BufWriter::new(File::create(path)?);
+        Ok(match compression {
+            Compression::None => Self::Plain(writer),
+            Compression::Gzip => Self::Gzip(GzEncoder::new(writer, GzCompression::default())),
+            Compression::Zstd => Self::Zstd(zstd::Encoder::new(writer, 0)?),
+        })
+    }
+
+    pub fn finish(self) -> io::Result<()> {
+        match self {
+            Self::Plain(mut writer) => writer.flush(),
+            Self::Gzip(encoder) => encoder.finish()?.flush(),
+            Self::Zstd(encoder) => encoder.finish()?.flush(),
+        }
+    }
+}
+
+impl Write for ExportWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Plain(writer) => writer.write(buf),
+            Self::Gzip(encoder) => encoder.write(buf),
+            Self::Zstd(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Plain(writer) => writer.flush(),
+            Self::Gzip(encoder) => encoder.flush(),
+            Self::Zstd(encoder) => encoder.flush(),
+        }
+    }
+}
+
+/// The settings shared by all export formats.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportOptions {
+    /// Not serialized, since a custom filter can only be restored if its script is loaded. See
+    /// `ExportCheckpoint::point_filter_name`.
+    #[serde(skip)]
+    pub point_filter: PointFilter,
+    pub status_filter: PointStatusFilter,
+    pub include_small_w: bool,
+    pub w_range: RangeF32,
+}
+
+/// The ranges of w values to export, optionally excluding [-1, 1].
+pub fn export_w_ranges(w_range: RangeF32, include_small_w: bool) -> Vec<RangeF32> {
+    if include_small_w {
+        vec![w_range]
+    } else {
+        let (left, right) = w_range.cut_out(&RangeF32::inclusive_exclusive(-1.0, 1.0));
+        vec![left, right]
+    }
+}
+
+/// The approximate size of an export, extrapolated from a sample of evenly spaced points.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExportEstimate {
+    /// The number of floats that will be checked.
+    pub count: usize,
+    /// The expected size in bytes, or None if it depends on how points group into runs.
+    pub bytes: Option<u64>,
+    /// The size in bytes if every float is written on its own row (before compression).
+    pub max_bytes: u64,
+}
+
+pub fn estimate_export_size(
+    seam: &Seam,
+    format: ExportFormat,
+    options: &ExportOptions,
+) -> ExportEstimate {
+    let w_ranges = export_w_ranges(options.w_range, options.include_small_w);
+    let count: usize = w_ranges.iter().map(|range| range.count()).sum();
+
+    let mut header = Vec::new();
+    match format.run_format() {
+        None => write_points_header(&mut header, format).unwrap(),
+        Some(run_format) => write_runs_header(&mut header, run_format, seam).unwrap(),
+    }
+
+    let num_samples = count.min(ESTIMATE_SAMPLES);
+    let mut sample_bytes = 0;
+    let mut matching_bytes = 0;
+    let mut row = Vec::new();
+    for i in 0..num_samples {
+        let mut index = (i as u64 * count as u64 / num_samples as u64) as usize;
+        let w = w_ranges
+            .iter()
+            .find_map(|range| {
+                let w = range.nth(index);
+                index = index.saturating_sub(range.count());
+                w
+            })
+            .unwrap();
+
+        let (y, status) = seam.check_point(w, &options.point_filter);
+        row.clear();
+        match format.run_format() {
+            None => write_point(&mut row, format, seam, w, y, status).unwrap(),
+            Some(run_format) => {
+                write_run(&mut row, run_format, &SeamRun::new(w, y, status)).unwrap()
+            }
+        }
+
+        sample_bytes += row.len() as u64;
+        if options.status_filter.matches(status) {
+            matching_bytes += row.len() as u64;
+        }
+    }
+
+    let extrapolate = |bytes: u64| {
+        let rows = if num_samples == 0 {
+            0
+        } else {
+            bytes * count as u64 / num_samples as u64
+        };
+        header.len() as u64 + rows
+    };
+    ExportEstimate {
+        count,
+        bytes: match format {
+            ExportFormat::Points | ExportFormat::Positions => Some(extrapolate(matching_bytes)),
+            ExportFormat::RunsCsv | ExportFormat::RunsBinary => None,
+        },
+        max_bytes: extrapolate(sample_bytes),
+    }
+}
+
+/// The free space on the disk that `path` would be written to, if it can be determined.
+pub fn available_space(path: impl AsRef<Path>) -> Option<u64> {
+    let path = path::absolute(path).ok()?;
+    Disks::new_with_refreshed_list()
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+        .map(|disk| disk.available_space())
+}
+
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[unit])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+fn cancelled() -> io::Error {
+    io::Error::new(io::ErrorKind::Interrupted, "export cancelled")
+}
+
+fn write_points_header(writer: &mut impl Write, format: ExportFormat) -> io::Result<()> {
+    match format {
+        ExportFormat::Positions => Ok(()),
+        _ => writeln!(writer, "x,x hex,y,y hex,z,z hex,type"),
+    }
+}
+
+fn write_point(
+    writer: &mut impl Write,
+    format: ExportFormat,
+    seam: &Seam,
+    w: f32,
+    y: f32,
+    status: PointStatus,
+) -> io::Result<()> {
+    let [x, y, z] = seam.point_at(w, y);
+    match format {
+        ExportFormat::Positions => writeln!(
+            writer,
+            "{}\t{}\t{}\t{:08X}\t{:08X}\t{:08X}",
+            x,
+            y,
+            z,
+            x.to_bits(),
+            y.to_bits(),
+            z.to_bits(),
+        ),
+        _ => writeln!(
+            writer,
+            "{},{:#08X},{},{:#08X},{},{:#08X},{}",
+            x,
+            x.to_bits(),
+            y,
+            y.to_bits(),
+            z,
+            z.to_bits(),
+            status,
+        ),
+    }
+}
+
+/// A maximal run of consecutive floats along a seam that have the same status.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SeamRun {
+    pub start_w: f32,
+    /// Inclusive.
+    pub end_w: f32,
+    pub count: u64,
+    pub y_min: f32,
+    pub y_max: f32,
+    pub status: PointStatus,
+}
+
+impl SeamRun {
+    pub fn new(w: f32, y: f32, status: PointStatus) -> Self {
+        Self {
+            start_w: w,
+            end_w: w,
+            count: 1,
+            y_min: y,
+            y_max: y,
+            status,
+        }
+    }
+
+    pub fn push(&mut self, w: f32, y: f32) {
+        self.end_w = w;
+        self.count += 1;
+        self.y_min = self.y_min.min(y);
+        self.y_max = self.y_max.max(y);
+    }
+}
+
+fn status_to_u8(status: PointStatus) -> u8 {
+    match status {
+        PointStatus::None => 0,
+        PointStatus::Gap => 1,
+        PointStatus::Overlap => 2,
+    }
+}
+
+fn status_from_u8(value: u8) -> io::Result<PointStatus> {
+    match value {
+        0 => Ok(PointStatus::None),
+        1 => Ok(PointStatus::Gap),
+        2 => Ok(PointStatus::Overlap),
+        _ => Err(invalid_data(format!("invalid status: {}", value))),
+    }
+}
+
+fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+fn axis_name(projection_axis: ProjectionAxis) -> &'static str {
+    match projection_axis {
+        ProjectionAxis::X => "z",
+        ProjectionAxis::Z => "x",
+    }
+}
+
+fn write_runs_header(writer: &mut impl Write, format: RunFormat, seam: &Seam) -> io::Result<()> {
+    match format {
+        RunFormat::Csv => {
+            let w = axis_name(seam.edge1.projection_axis);
+            writeln!(
+                writer,
+                "{w} start,{w} start hex,{w} end,{w} end hex,count,y min,y min hex,y max,y max hex,type"
+            )
+        }
+        RunFormat::Binary => {
+            writer.write_all(RUNS_MAGIC)?;
+            writer.write_all(&[RUNS_VERSION])?;
+            writer.write_all(&[match seam.edge1.projection_axis {
+                ProjectionAxis::X => 0,
+                ProjectionAxis::Z => 1,
+            }])?;
+            for coord in seam.endpoints.0.iter().chain(seam.endpoints.1.iter()) {
+                writer.write_all(&coord.to_le_bytes())?;
+            }
+            Ok(())
+        }
+    }
+}
+
+fn write_run(writer: &mut impl Write, format: RunFormat, run: &SeamRun) -> io::Result<()> {
+    match format {
+        RunFormat::Csv => writeln!(
+            writer,
+            "{},{:#08X},{},{:#08X},{},{},{:#08X},{},{:#08X},{}",
+            run.start_w,
+            run.start_w.to_bits(),
+            run.end_w,
+            run.end_w.to_bits(),
+            run.count,
+            run.y_min,
+            run.y_min.to_bits(),
+            run.y_max,
+            run.y_max.to_bits(),
+            run.status,
+        ),
+        RunFormat::Binary => {
+            writer.write_all(&run.start_w.to_le_bytes())?;
+            writer.write_all(&run.end_w.to_le_bytes())?;
+            writer.write_all(&run.count.to_le_bytes())?;
+            writer.write_all(&run.y_min.to_le_bytes())?;
+            writer.write_all(&run.y_max.to_le_bytes())?;
+            writer.write_all(&[status_to_u8(run.status)])
+        }
+    }
+}
+
+/// Export a seam with one CSV row per float.
+///
+/// If `cancel` is set, the export stops and returns an `Interrupted` error.
+pub fn save_seam_to_csv(
+    writer: &mut impl Write,
+    set_progress: impl FnMut(Option<ExportProgress>),
+    cancel: &AtomicBool,
+    seam: &Seam,
+    options: &ExportOptions,
+) -> io::Result<()> {
+    let job = ExportJob {
+        seam: seam.clone(),
+        format: ExportFormat::Points,
+        compression: Compression::None,
+        options: options.clone(),
+    };
+    write_export(
+        writer,
+        &job,
+        &mut ExportState::default(),
+        set_progress,
+        cancel,
+        |_, _| Ok(()),
+    )
+}
+
+/// Export a seam as runs of consecutive floats with the same status.
+///
+/// Floats that don't match `status_filter` are omitted and end the current run. If `cancel` is
+/// set, the export stops and returns an `Interrupted` error.
+pub fn save_seam_runs(
+    writer: &mut impl Write,
+    format: RunFormat,
+    set_progress: impl FnMut(Option<ExportProgress>),
+    cancel: &AtomicBool,
+    seam: &Seam,
+    options: &ExportOptions,
+) -> io::Result<()> {
+    let job = ExportJob {
+        seam: seam.clone(),
+        format: match format {
+            RunFormat::Csv => ExportFormat::RunsCsv,
+            RunFormat::Binary => ExportFormat::RunsBinary,
+        },
+        compression: Compression::None,
+        options: options.clone(),
+    };
+    write_export(
+        writer,
+        &job,
+        &mut ExportState::default(),
+        set_progress,
+        cancel,
+        |_, _| Ok(()),
+    )
+}
+
+/// How far an export has gotten.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct ExportState {
+    /// Whether the header has been written. An export can be cancelled before its first point,
+    /// after the header has already been written.
+    header_written: bool,
+    /// Index into `export_w_ranges`.
+    range_index: usize,
+    /// The next w value to check in the current range, or None to start from the beginning.
+    next_w: Option<f32>,
+    complete: usize,
+    current_run: Option<SeamRun>,
+}
+
+fn write_export<W: Write>(
+    writer: &mut W,
+    job: &ExportJob,
+    state: &mut ExportState,
+    mut set_progress: impl FnMut(Option<ExportProgress>),
+    cancel: &AtomicBool,
+    mut checkpoint: impl FnMut(&mut W, &ExportState) -> io::Result<()>,
+) -> io::Result<()> {
+    let ExportJob {
+        seam,
+        format,
+        options,
+        ..
+    } = job;
+
+    if !state.header_written {
+        match format.run_format() {
+            None => write_points_header(writer, *format)?,
+            Some(run_format) => write_runs_header(writer, run_format, seam)?,
+        }
+        state.header_written = true;
+    }
+
+    let w_ranges = export_w_ranges(options.w_range, options.include_small_w);
+    let total = w_ranges.iter().map(|range| range.count()).sum();
+    let mut last_checkpoint = Instant::now();
+
+    while let Some(range) = w_ranges.get(state.range_index) {
+        let start = state.next_w.unwrap_or(range.start);
+
+        for w in RangeF32::inclusive_exclusive(start, range.end).iter() {
+            if state.complete.is_multiple_of(100_000) {
+                if cancel.load(Ordering::Relaxed) {
+                    checkpoint(writer, state)?;
+                    return Err(cancelled());
+                }
+                if last_checkpoint.elapsed() >= CHECKPOINT_INTERVAL {
+                    checkpoint(writer, state)?;
+                    last_checkpoint = Instant::now();
+                }
+                set_progress(Some(ExportProgress {
+                    complete: state.complete,
+                    total,
+                }));
+            }
+
+            let (y, status) = seam.check_point(w, &options.point_filter);
+            state.next_w = Some(next_f32(w));
+            state.complete += 1;
+
+            match format.run_format() {
+                None => {
+                    if options.status_filter.matches(status) {
+                        write_point(writer, *format, seam, w, y, status)?;
+                    }
+                }
+                Some(run_format) => {
+                    if !options.status_filter.matches(status) {
+                        if let Some(run) = state.current_run.take() {
+                            write_run(writer, run_format, &run)?;
+                        }
+                        continue;
+                    }
+
+                    match &mut state.current_run {
+                        Some(run) if run.status == status => run.push(w, y),
+                        _ => {
+                            if let Some(run) = state.current_run.take() {
+                                write_run(writer, run_format, &run)?;
+                            }
+                            state.current_run = Some(SeamRun::new(w, y, status));
+                        }
+                    }
+                }
+            }
+        }
+
+        if let (Some(run_format), Some(run)) = (format.run_format(), state.current_run.take()) {
+            write_run(writer, run_format, &run)?;
+        }
+        state.range_index += 1;
+        state.next_w = None;
+    }
+
+    writer.flush()?;
+    set_progress(None);
+    Ok(())
+}
+
+/// An export that can be written to a file and resumed after being interrupted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportJob {
+    pub seam: Seam,
+    pub format: ExportFormat,
+    pub compression: Compression,
+    pub options: ExportOptions,
+}
+
+/// The state of an interrupted export, stored next to the output file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportCheckpoint {
+    pub job: ExportJob,
+    /// Stored by name, since a custom filter can only be restored once its script is loaded
+    /// again.
+    point_filter_name: String,
+    state: ExportState,
+    /// The length of the output file when the checkpoint was written.
+    offset: u64,
+}
+
+impl ExportCheckpoint {
+    pub fn progress(&self) -> ExportProgress {
+        let w_ranges = export_w_ranges(self.job.options.w_range, self.job.options.include_small_w);
+        ExportProgress {
+            complete: self.state.complete,
+            total: w_ranges.iter().map(|range| range.count()).sum(),
+        }
+    }
+}
+
+pub fn checkpoint_path(path: impl AsRef<Path>) -> PathBuf {
+    let mut path = path.as_ref().as_os_str().to_owned();
+    path.push(".checkpoint");
+    PathBuf::from(path)
+}
+
+/// Load the checkpoint for an interrupted export to `path`, if there is one.
+pub fn load_checkpoint(path: impl AsRef<Path>) -> Option<ExportCheckpoint> {
+    let text = fs::read_to_string(checkpoint_path(path)).ok()?;
+    serde_json::from_str(&text).ok()
+}
+
+fn save_checkpoint(path: &Path, checkpoint: &ExportCheckpoint) -> io::Result<()> {
+    // Write to a temporary file first so that a crash can't leave a partial checkpoint
+    let checkpoint_path = checkpoint_path(path);
+    let mut temp_path = checkpoint_path.clone().into_os_string();
+    temp_path.push(".tmp");
+    fs::write(&temp_path, serde_json::to_string_pretty(checkpoint)?)?;
+    fs::rename(&temp_path, &checkpoint_path)
+}
+
+/// Export to the file at `path`.
+///
+/// Uncompressed exports periodically save a checkpoint to `<path>.checkpoint` so that they can be
+/// continued with `resume_export` if the program closes or the export is cancelled. The
+/// checkpoint is deleted when the export finishes.
+pub fn export_to_file(
+    path: impl AsRef<Path>,
+    job: &ExportJob,
+    set_progress: impl FnMut(Option<ExportProgress>),
+    cancel: &AtomicBool,
+) -> io::Result<()> {
+    let path = path.as_ref();
+    let _ = fs::remove_file(checkpoint_path(path));
+
+    if job.compression != Compression::None {
+        let mut writer = ExportWriter::create(path, job.compression)?;
+        write_export(
+            &mut writer,
+            job,
+            &mut ExportState::default(),
+            set_progress,
+            cancel,
+            |_, _| Ok(()),
+        )?;
+        return writer.finish();
+    }
+
+    let file = File::create(path)?;
+    write_file_with_checkpoints(
+        path,
+        file,
+        job,
+        ExportState::default(),
+        set_progress,
+        cancel,
+    )
+}
+
+/// Continue an export to `path` from its checkpoint.
+///
+/// The output is identical to an export that was never interrupted.
+pub fn resume_export(
+    path: impl AsRef<Path>,
+    set_progress: impl FnMut(Option<ExportProgress>),
+    cancel: &AtomicBool,
+) -> io::Result<()> {
+    let path = path.as_ref();
+    let checkpoint = load_checkpoint(path)
+        .ok_or_else(|| invalid_data(format!("no checkpoint for {}", path.display())))?;
+
+    let mut job = checkpoint.job;
+    job.options.point_filter =
+        PointFilter::from_name(&checkpoint.point_filter_name).ok_or_else(|| {
+            invalid_data(format!(
+                "point filter {} is not loaded",
+                checkpoint.point_filter_name
+            ))
+        })?;
+
+    let mut file = OpenOptions::new().write(true).open(path)?;
+    if file.metadata()?.len() < checkpoint.offset {
+        return Err(invalid_data(format!(
+            "{} is shorter than its checkpoint",
+            path.display()
+        )));
+    }
+    file.set_len(checkpoint.offset)?;
+    file.seek(SeekFrom::End(0))?;
+
+    write_file_with_checkpoints(path, file, &job, checkpoint.state, set_progress, cancel)
+}
+
+fn write_file_with_checkpoints(
+    path: &Path,
+    file: File,
+    job: &ExportJob,
+    mut state: ExportState,
+    set_progress: impl FnMut(Option<ExportProgress>),
+    cancel: &AtomicBool,
+) -> io::Result<()> {
+    let mut writer = BufWriter::new(file);
+    write_export(
+        &mut writer,
+        job,
+        &mut state,
+        set_progress,
+        cancel,
+        |writer, state| {
+            writer.flush()?;
+            let checkpoint = ExportCheckpoint {
+                job: job.clone(),
+                point_filter_name: job.options.point_filter.to_string(),
+                state: state.clone(),
+                offset: writer.stream_position()?,
+            };
+            save_checkpoint(path, &checkpoint)
+        },
+    )?;
+
+    let _ = fs::remove_file(checkpoint_path(path));
+    Ok(())
+}
+
+/// A point read from a file written by `save_seam_to_csv`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExportedPoint {
+    pub w: f32,
+    pub y: f32,
+    pub status: PointStatus,
+}
+
+/// Read the points from a CSV written by `save_seam_to_csv`, sorted by w.
+///
+/// Files with only w and y columns, written by older versions, are also supported. At most
+/// `max_points` points are read, and the returned bool is true if the file contains more.
+///
+/// Progress is reported in bytes. If `cancel` is set, reading stops and returns an `Interrupted`
+/// error.
+pub fn read_points_csv(
+    path: impl AsRef<Path>,
+    mut set_progress: impl FnMut(ExportProgress),
+    cancel: &AtomicBool,
+    projection_axis: ProjectionAxis,
+    max_points: usize,
+) -> io::Result<(Vec<ExportedPoint>, bool)> {
+    let file = File::open(path)?;
+    let total = file.metadata()?.len() as usize;
+    let mut lines = BufReader::new(file).lines();
+    let header = lines.next().ok_or_else(|| invalid_data("empty file"))??;
+    let columns: Vec<&str> = header.trim().split(',').collect();
+
+    let w_name = axis_name(projection_axis);
+    let (w_column, y_column) = match columns[..] {
+        ["x", "x hex", "y", "y hex", "z", "z hex", "type"] => match projection_axis {
+            ProjectionAxis::X => (5, 3),
+            ProjectionAxis::Z => (1, 3),
+        },
+        [w, _, "y", "y hex", "type"] if w == w_name => (1, 3),
+        [w, _, "y", "y hex", "type"] => {
+            return Err(invalid_data(format!(
+                "file has {} values, but the seam uses {}",
+                w, w_name
+            )));
+        }
+        _ => return Err(invalid_data(format!("unrecognized header: {}", header))),
+    };
+
+    let parse_hex = |text: &str| {
+        u32::from_str_radix(text.trim_start_matches("0x"), 16)
+            .map(f32::from_bits)
+            .map_err(|_| invalid_data(format!("invalid float: {}", text)))
+    };
+
+    let mut points = Vec::new();
+    let mut truncated = false;
+    let mut complete = header.len() + 1;
+    for (i, line) in lines.enumerate() {
+        let line = line?;
+        complete += line.len() + 1;
+        if i % PROGRESS_INTERVAL == 0 {
+            if cancel.load(Ordering::Relaxed) {
+                return Err(cancelled());
+            }
+            set_progress(ExportProgress { complete, total });
+        }
+
+        if line.trim().is_empty() {
+            continue;
+        }
+        if points.len() >= max_points {
+            truncated = true;
+            break;
+        }
+
+        let fields: Vec<&str> = line.trim().split(',').collect();
+        if fields.len() != columns.len() {
+            return Err(invalid_data(format!("invalid row: {}", line)));
+        }
+        points.push(ExportedPoint {
+            w: parse_hex(fields[w_column])?,
+            y: parse_hex(fields[y_column])?,
+            status: fields[fields.len() - 1].parse().map_err(invalid_data)?,
+        });
+    }
+
+    points.sort_by(|a, b| a.w.total_cmp(&b.w));
+    Ok((points, truncated))
+}
+
+/// Reads a file written by `save_seam_runs`, in either format and with any compression.
+pub struct SeamRunReader {
+    format: RunFormat,
+    projection_axis: ProjectionAxis,
+    endpoints: Option<([i16; 3], [i16; 3])>,
+    reader: Box<dyn BufRead>,
+}
+
+impl SeamRunReader {
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut file = BufReader::new(File::open(path)?);
+
+        let head = file.fill_buf()?;
+        let mut reader: Box<dyn BufRead> = if head.starts_with(GZIP_MAGIC) {
+            Box::new(BufReader::new(MultiGzDecoder::new(file)))
+        } else if head.starts_with(ZSTD_MAGIC) {
+            Box::new(BufReader::new(zstd::Decoder::with_buffer(file)?))
+        } else {
+            Box::new(file)
+        };
+
+        if reader.fill_buf()?.starts_with(RUNS_MAGIC) {
+            let mut header = [0; RUNS_MAGIC.len() + 2 + 12];
+            reader.read_exact(&mut header)?;
+
+            let version = header[RUNS_MAGIC.len()];
+            if version != RUNS_VERSION {
+                return Err(invalid_data(format!("unsupported version: {}", version)));
+            }
+            let projection_axis = match header[RUNS_MAGIC.len() + 1] {
+                0 => ProjectionAxis::X,
+                1 => ProjectionAxis::Z,
+                axis => return Err(invalid_data(format!("invalid projection axis: {}", axis))),
+            };
+            let coords: Vec<i16> = header[RUNS_MAGIC.len() + 2..]
+                .chunks(2)
+                .map(|bytes| i16::from_le_bytes([bytes[0], bytes[1]]))
+                .collect();
+
+            Ok(Self {
+                format: RunFormat::Binary,
+                projection_axis,
+                endpoints: Some((
+                    [coords[0], coords[1], coords[2]],
+                    [coords[3], coords[4], coords[5]],
+                )),
+                reader,
+            })
+        } else {
+            let mut header = String::new();
+            reader.read_line(&mut header)?;
+            let projection_axis = if header.starts_with("z start,") {
+                ProjectionAxis::X
+            } else if header.starts_with("x start,") {
+                ProjectionAxis::Z
+            } else {
+                return Err(invalid_data("not a seam runs file"));
+            };
+
+            Ok(Self {
+                format: RunFormat::Csv,
+                projection_axis,
+                endpoints: None,
+                reader,
+            })
+        }
+    }
+
+    pub fn format(&self) -> RunFormat {
+        self.format
+    }
+
+    pub fn projection_axis(&self) -> ProjectionAxis {
+        self.projection_axis
+    }
+
+    /// The seam's endpoints. Only stored in the binary format.
+    pub fn endpoints(&self) -> Option<([i16; 3], [i16; 3])> {
+        self.endpoints
+    }
+
+    fn read_binary_run(&mut self) -> io::Result<Option<SeamRun>> {
+        let mut record = [0; 25];
+        if self.reader.fill_buf()?.is_empty() {
+            return Ok(None);
+        }
+        self.reader.read_exact(&mut record)?;
+
+        let read_f32 =
+            |offset: usize| f32::from_le_bytes(record[offset..offset + 4].try_into().unwrap());
+        Ok(Some(SeamRun {
+            start_w: read_f32(0),
+            end_w: read_f32(4),
+            count: u64::from_le_bytes(record[8..16].try_into().unwrap()),
+            y_min: read_f32(16),
+            y_max: read_f32(20),
+            status: status_from_u8(record[24])?,
+        }))
+    }
+
+    fn read_csv_run(&mut self) -> io::Result<Option<SeamRun>> {
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+
+        let fields: Vec<&str> = line.trim_end().split(',').collect();
+        if fields.len() != 10 {
+            return Err(invalid_data(format!("invalid row: {}", line.trim_end())));
+        }
+        let parse_hex = |field: &str| {
+            u32::from_str_radix(field.trim_start_matches("0x").trim_start_matches("0X"), 16)
+                .map(f32::from_bits)
+                .map_err(|error| invalid_data(error.to_string()))
+        };
+
+        Ok(Some(SeamRun {
+            start_w: parse_hex(fields[1])?,
+            end_w: parse_hex(fields[3])?,
+            count: fields[4]
+                .parse()
+                .map_err(|error: std::num::ParseIntError| invalid_data(error.to_string()))?,
+            y_min: parse_hex(fields[6])?,
+            y_max: parse_hex(fields[8])?,
+            status: fields[9].parse().map_err(invalid_data)?,
+        }))
+    }
+}
+
+impl Iterator for SeamRunReader {
+    type Item = io::Result<SeamRun>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let run = match self.format {
+            RunFormat::Csv => self.read_csv_run(),
+            RunFormat::Binary => self.read_binary_run(),
+        };
+        run.transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::edge::ProjectedPoint;
+    use std::str;
+
+    /// A seam between two walls facing +z that share a sloped edge.
+    fn test_seam() -> Seam {
+        Seam::between(
+            ([0, 0, 0], [100, 37, 0]),
+            [0.0, 0.0, 1.0],
+            ([100, 37, 0], [0, 0, 0]),
+            [0.0, 0.0, 1.0],
+        )
+        .unwrap()
+    }
+
+    fn test_options(w_range: RangeF32) -> ExportOptions {
+        ExportOptions {
+            point_filter: PointFilter::None,
+            status_filter: PointStatusFilter::AllPoints,
+            include_small_w: false,
+            w_range,
+        }
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("sm64-seam-tool-{}-{}", std::process::id(), name))
+    }
+
+    #[test]
+    fn seam_runs_round_trip() {
+        let seam = test_seam();
+        let options = test_options(RangeF32::inclusive_exclusive(50.0, 50.0625));
+
+        let mut expected: Vec<SeamRun> = Vec::new();
+        for w in options.w_range.iter() {
+            let (y, status) = seam.check_point(w, &options.point_filter);
+            match expected.last_mut() {
+                Some(run) if run.status == status => run.push(w, y),
+                _ => expected.push(SeamRun::new(w, y, status)),
+            }
+        }
+        assert!(expected.len() > 1);
+
+        for format in [RunFormat::Csv, RunFormat::Binary] {
+            for compression in [Compression::Gzip, Compression::Zstd] {
+                let path = temp_path(&format!("runs-{:?}-{:?}", format, compression));
+                let mut writer = ExportWriter::create(&path, compression).unwrap();
+                save_seam_runs(
+                    &mut writer,
+                    format,
+                    |_| {},
+                    &AtomicBool::new(false),
+                    &seam,
+                    &options,
+                )
+                .unwrap();
+                writer.finish().unwrap();
+
+                let reader = SeamRunReader::open(&path).unwrap();
+                assert_eq!(reader.format(), format);
+                assert_eq!(reader.projection_axis(), seam.edge1.projection_axis);
+                if format == RunFormat::Binary {
+                    assert_eq!(reader.endpoints(), Some(seam.endpoints));
+                }
+                let runs: Vec<SeamRun> = reader.collect::<io::Result<_>>().unwrap();
+                let _ = fs::remove_file(&path);
+
+                assert_eq!(runs, expected, "{:?} {:?}", format, compression);
+            }
+        }
+    }
+
+    #[test]
+    fn exported_positions_lie_on_wall_plane() {
+        // Walls that face diagonally, so the third coordinate depends on the plane
+        let walls = [
+            ([0, 0, 0], [80, 37, -60], [0.6, 0.0, 0.8]),
+            ([0, 0, 0], [-60, 37, 80], [0.8, 0.0, 0.6]),
+        ];
+        for (vertex1, vertex2, normal) in walls {
+            let seam =
+                Seam::between((vertex1, vertex2), normal, (vertex2, vertex1), normal).unwrap();
+            let plane = seam.plane1.unwrap();
+            let w_start = match seam.edge1.projection_axis {
+                ProjectionAxis::X => 40.0,
+                ProjectionAxis::Z => 20.0,
+            };
+            let options = test_options(RangeF32::inclusive_exclusive(w_start, w_start + 0.001));
+
+            let mut output = Vec::new();
+            save_seam_to_csv(
+                &mut output,
+                |_| {},
+                &AtomicBool::new(false),
+                &seam,
+                &options,
+            )
+            .unwrap();
+
+            let rows: Vec<Vec<f32>> = str::from_utf8(&output)
+                .unwrap()
+                .lines()
+                .skip(1)
+                .map(|line| {
+                    let columns: Vec<&str> = line.split(',').collect();
+                    [0, 2, 4].map(|i| columns[i].parse().unwrap()).to_vec()
+                })
+                .collect();
+            assert_eq!(rows.len(), options.w_range.count());
+
+            for (row, w) in rows.iter().zip(options.w_range.iter()) {
+                let point =
+                    ProjectedPoint::project([row[0], row[1], row[2]], seam.edge1.projection_axis);
+                assert_eq!(point.w, w);
+
+                let offset =
+                    (0..3).map(|i| plane.normal[i] * row[i]).sum::<f32>() + plane.origin_offset;
+                assert!(offset.abs() < 1e-3, "{:?} is {} from the wall", row, offset);
+            }
+        }
+    }
+
+    #[test]
+    fn points_csv_round_trip() {
+        let seam = test_seam();
+        let options = test_options(RangeF32::inclusive_exclusive(50.0, 50.001));
+        let path = temp_path("points.csv");
+        let mut writer = BufWriter::new(File::create(&path).unwrap());
+        save_seam_to_csv(
+            &mut writer,
+            |_| {},
+            &AtomicBool::new(false),
+            &seam,
+            &options,
+        )
+        .unwrap();
+        writer.flush().unwrap();
+        drop(writer);
+
+        let mut last_progress = None;
+        let (points, truncated) = read_points_csv(
+            &path,
+            |progress| last_progress = Some(progress),
+            &AtomicBool::new(false),
+            seam.edge1.projection_axis,
+            usize::MAX,
+        )
+        .unwrap();
+        assert!(!truncated);
+        let expected: Vec<ExportedPoint> = options
+            .w_range
+            .iter()
+            .map(|w| {
+                let (y, status) = seam.check_point(w, &options.point_filter);
+                ExportedPoint { w, y, status }
+            })
+            .collect();
+        assert_eq!(points, expected);
+        let last_progress = last_progress.unwrap();
+        assert!(last_progress.complete <= last_progress.total);
+
+        let (points, truncated) = read_points_csv(
+            &path,
+            |_| {},
+            &AtomicBool::new(false),
+            seam.edge1.projection_axis,
+            10,
+        )
+        .unwrap();
+        assert!(truncated);
+        assert_eq!(points, expected[..10]);
+
+        let error = read_points_csv(
+            &path,
+            |_| {},
+            &AtomicBool::new(true),
+            seam.edge1.projection_axis,
+            usize::MAX,
+        )
+        .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::Interrupted);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn resumed_export_matches_uninterrupted_export() {
+        let job = ExportJob {
+            seam: test_seam(),
+            format: ExportFormat::RunsCsv,
+            compression: Compression::None,
+            // A little over 100,000 floats, so that the export can be cancelled partway
+            options: test_options(RangeF32::inclusive_exclusive(50.0, 50.4)),
+        };
+
+        let expected_path = temp_path("uninterrupted.csv");
+        export_to_file(&expected_path, &job, |_| {}, &AtomicBool::new(false)).unwrap();
+        let expected = fs::read(&expected_path).unwrap();
+        let _ = fs::remove_file(&expected_path);
+
+        let path = temp_path("resumed.csv");
+        let cancel = AtomicBool::new(false);
+        let result = export_to_file(
+            &path,
+            &job,
+            |progress| {
+                if progress.is_some_and(|progress| progress.complete == 0) {
+                    cancel.store(true, Ordering::Relaxed);
+                }
+            },
+            &cancel,
+        );
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::Interrupted);
+
+        let checkpoint = load_checkpoint(&path).unwrap();
+        let progress = checkpoint.progress();
+        assert!(progress.complete > 0 && progress.complete < progress.total);
+
+        resume_export(&path, |_| {}, &AtomicBool::new(false)).unwrap();
+        assert!(load_checkpoint(&path).is_none());
+        let resumed = fs::read(&path).unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert!(resumed == expected, "resumed export differs");
+    }
+
+    #[test]
+    fn export_resumed_before_first_point() {
+        let job = ExportJob {
+            seam: test_seam(),
+            format: ExportFormat::Points,
+            compression: Compression::None,
+            options: test_options(RangeF32::inclusive_exclusive(50.0, 50.001)),
+        };
+
+        let expected_path = temp_path("uninterrupted-immediately.csv");
+        export_to_file(&expected_path, &job, |_| {}, &AtomicBool::new(false)).unwrap();
+        let expected = fs::read(&expected_path).unwrap();
+        let _ = fs::remove_file(&expected_path);
+
+        let path = temp_path("resumed-immediately.csv");
+        let result = export_to_file(&path, &job, |_| {}, &AtomicBool::new(true));
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::Interrupted);
+        assert_eq!(load_checkpoint(&path).unwrap().progress().complete, 0);
+
+        resume_export(&path, |_| {}, &AtomicBool::new(false)).unwrap();
+        let resumed = fs::read(&path).unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert!(resumed == expected, "resumed export differs");
+    }
+}
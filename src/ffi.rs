@@ -0,0 +1,370 @@
+//! C API for classifying points on a seam without running the GUI.
+//!
+//! Intended for emulator scripting environments (e.g. mupen-lua) that can load native libraries.
+//! The header `include/sm64_seam_tool.h` is generated by the build script. It is checked in, so
+//! rebuild and commit it after changing this file.
+
+use crate::{
+    edge::ProjectedPoint,
+    float_range::{RangeF32, next_f32, prev_f32},
+    seam::{PointFilter, PointStatus, Seam},
+};
+use std::{ptr, slice};
+
+/// See `PointFilter`. Filters defined by scripts are not available through the C API.
+///
+/// Functions take the filter as a `uint32_t` so that invalid values from C can be rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(C)]
+pub enum SeamPointFilter {
+    None,
+    IntY,
+    QuarterIntY,
+}
+
+impl SeamPointFilter {
+    fn from_u32(value: u32) -> Option<Self> {
+        match value {
+            0 => Some(SeamPointFilter::None),
+            1 => Some(SeamPointFilter::IntY),
+            2 => Some(SeamPointFilter::QuarterIntY),
+            _ => None,
+        }
+    }
+}
+
+impl From<SeamPointFilter> for PointFilter {
+    fn from(filter: SeamPointFilter) -> Self {
+        match filter {
+            SeamPointFilter::None => PointFilter::None,
+            SeamPointFilter::IntY => PointFilter::IntY,
+            SeamPointFilter::QuarterIntY => PointFilter::QuarterIntY,
+        }
+    }
+}
+
+/// See `PointStatus`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(C)]
+pub enum SeamPointStatus {
+    Gap,
+    Overlap,
+    None,
+}
+
+impl From<PointStatus> for SeamPointStatus {
+    fn from(status: PointStatus) -> Self {
+        match status {
+            PointStatus::Gap => SeamPointStatus::Gap,
+            PointStatus::Overlap => SeamPointStatus::Overlap,
+            PointStatus::None => SeamPointStatus::None,
+        }
+    }
+}
+
+unsafe fn read_vertices(vertices: *const i16) -> ([i16; 3], [i16; 3]) {
+    let v = unsafe { slice::from_raw_parts(vertices, 6) };
+    ([v[0], v[1], v[2]], [v[3], v[4], v[5]])
+}
+
+unsafe fn read_normal(normal: *const f32) -> [f32; 3] {
+    let n = unsafe { slice::from_raw_parts(normal, 3) };
+    [n[0], n[1], n[2]]
+}
+
+/// Find the seam between an edge of one wall and an edge of another.
+///
+/// `vertices1` and `vertices2` each point to 6 values: the two endpoints of the edge, in the
+/// wall's vertex order. `normal1` and `normal2` each point to 3 values.
+///
+/// Returns null if the edges do not form a seam. The result must be freed with `seam_free`.
+///
+/// # Safety
+///
+/// All pointers must be valid for reads of the given number of values.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn seam_between(
+    vertices1: *const i16,
+    normal1: *const f32,
+    vertices2: *const i16,
+    normal2: *const f32,
+) -> *mut Seam {
+    if vertices1.is_null() || normal1.is_null() || vertices2.is_null() || normal2.is_null() {
+        return ptr::null_mut();
+    }
+
+    let seam = unsafe {
+        Seam::between(
+            read_vertices(vertices1),
+            read_normal(normal1),
+            read_vertices(vertices2),
+            read_normal(normal2),
+        )
+    };
+    match seam {
+        Some(seam) => Box::into_raw(Box::new(seam)),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Free a seam returned by `seam_between`. Does nothing if `seam` is null.
+///
+/// # Safety
+///
+/// `seam` must be null or a pointer returned by `seam_between` that has not already been freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn seam_free(seam: *mut Seam) {
+    if !seam.is_null() {
+        drop(unsafe { Box::from_raw(seam) });
+    }
+}
+
+/// The range of w values covered by both edges of the seam.
+///
+/// # Safety
+///
+/// `seam` must be a valid pointer returned by `seam_between`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn seam_w_range(seam: *const Seam) -> RangeF32 {
+    match unsafe { seam.as_ref() } {
+        Some(seam) => seam.w_range(),
+        None => RangeF32::inclusive_exclusive(0.0, 0.0),
+    }
+}
+
+/// Classify the point on the seam with the given w value, and write the result to `status_out`.
+///
+/// `filter` is a `SeamPointFilter` value. Returns false without writing anything if `seam` or
+/// `status_out` is null or `filter` is invalid.
+///
+/// The y value of the gap or overlap (or an approximate y value on the seam if there is neither)
+/// is written to `y_out` if it is non-null.
+///
+/// # Safety
+///
+/// `seam` must be null or a valid pointer returned by `seam_between`, and `status_out` and `y_out`
+/// must be null or valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn seam_check_point(
+    seam: *const Seam,
+    w: f32,
+    filter: u32,
+    status_out: *mut SeamPointStatus,
+    y_out: *mut f32,
+) -> bool {
+    let (Some(seam), Some(status_out)) = (unsafe { seam.as_ref() }, unsafe { status_out.as_mut() })
+    else {
+        return false;
+    };
+    let Some(filter) = SeamPointFilter::from_u32(filter) else {
+        return false;
+    };
+
+    let (y, status) = seam.check_point(w, &filter.into());
+    *status_out = status.into();
+    if let Some(y_out) = unsafe { y_out.as_mut() } {
+        *y_out = y;
+    }
+    true
+}
+
+/// Return true if the projected point `(w, y)` passes the filter.
+///
+/// `filter` is a `SeamPointFilter` value. Returns false if it is invalid.
+#[unsafe(no_mangle)]
+pub extern "C" fn seam_point_filter_matches(filter: u32, w: f32, y: f32) -> bool {
+    match SeamPointFilter::from_u32(filter) {
+        Some(filter) => PointFilter::from(filter).matches(ProjectedPoint { w, y }),
+        None => false,
+    }
+}
+
+/// The range of floats from `min` to `max`, inclusive.
+#[unsafe(no_mangle)]
+pub extern "C" fn range_f32_inclusive(min: f32, max: f32) -> RangeF32 {
+    RangeF32::inclusive(min, max)
+}
+
+/// The number of floats in the range.
+#[unsafe(no_mangle)]
+pub extern "C" fn range_f32_count(range: RangeF32) -> usize {
+    range.count()
+}
+
+/// Pop the smallest float from the range and write it to `out`.
+///
+/// Returns false if the range is empty. Used to iterate over every float in a range:
+///
+/// ```c
+/// RangeF32 range = seam_w_range(seam);
+/// float w;
+/// while (range_f32_next(&range, &w)) { ... }
+/// ```
+///
+/// # Safety
+///
+/// `range` must be valid for reads and writes, and `out` must be valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn range_f32_next(range: *mut RangeF32, out: *mut f32) -> bool {
+    let (Some(range), Some(out)) = (unsafe { range.as_mut() }, unsafe { out.as_mut() }) else {
+        return false;
+    };
+    if range.is_empty() {
+        return false;
+    }
+
+    *out = range.start;
+    range.start = next_f32(range.start);
+    true
+}
+
+/// The next float larger than `x`, treating denormals as zero.
+#[unsafe(no_mangle)]
+pub extern "C" fn f32_next(x: f32) -> f32 {
+    next_f32(x)
+}
+
+/// The previous float smaller than `x`, treating denormals as zero.
+#[unsafe(no_mangle)]
+pub extern "C" fn f32_prev(x: f32) -> f32 {
+    prev_f32(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VERTICES1: [i16; 6] = [0, 0, 0, 100, 37, 0];
+    const VERTICES2: [i16; 6] = [100, 37, 0, 0, 0, 0];
+    const NORMAL: [f32; 3] = [0.0, 0.0, 1.0];
+
+    fn new_seam() -> *mut Seam {
+        unsafe {
+            seam_between(
+                VERTICES1.as_ptr(),
+                NORMAL.as_ptr(),
+                VERTICES2.as_ptr(),
+                NORMAL.as_ptr(),
+            )
+        }
+    }
+
+    #[test]
+    fn seam_between_matches_seam() {
+        let seam = new_seam();
+        assert!(!seam.is_null());
+        let expected = Seam::between(
+            ([0, 0, 0], [100, 37, 0]),
+            NORMAL,
+            ([100, 37, 0], [0, 0, 0]),
+            NORMAL,
+        );
+        assert_eq!(unsafe { seam.as_ref() }, expected.as_ref());
+        unsafe { seam_free(seam) };
+
+        // Edges that don't share both vertices
+        let other = [0, 0, 0, 50, 37, 0];
+        let seam = unsafe {
+            seam_between(
+                VERTICES1.as_ptr(),
+                NORMAL.as_ptr(),
+                other.as_ptr(),
+                NORMAL.as_ptr(),
+            )
+        };
+        assert!(seam.is_null());
+
+        let seam = unsafe {
+            seam_between(
+                VERTICES1.as_ptr(),
+                ptr::null(),
+                VERTICES2.as_ptr(),
+                NORMAL.as_ptr(),
+            )
+        };
+        assert!(seam.is_null());
+        unsafe { seam_free(ptr::null_mut()) };
+    }
+
+    #[test]
+    fn seam_check_point_matches_seam() {
+        let seam = new_seam();
+        let mut found_none = false;
+        for w in RangeF32::inclusive(50.0, 51.0).iter().step_by(61) {
+            for (filter, point_filter) in [
+                (SeamPointFilter::None, PointFilter::None),
+                (SeamPointFilter::QuarterIntY, PointFilter::QuarterIntY),
+            ] {
+                let (expected_y, expected_status) = unsafe { &*seam }.check_point(w, &point_filter);
+
+                let mut status = SeamPointStatus::Gap;
+                let mut y = f32::NAN;
+                assert!(unsafe { seam_check_point(seam, w, filter as u32, &mut status, &mut y) });
+                assert_eq!(status, expected_status.into());
+                assert_eq!(y.to_bits(), expected_y.to_bits());
+                found_none |= status == SeamPointStatus::None;
+
+                // y_out is optional
+                assert!(unsafe {
+                    seam_check_point(seam, w, filter as u32, &mut status, ptr::null_mut())
+                });
+            }
+        }
+        assert!(found_none);
+        unsafe { seam_free(seam) };
+    }
+
+    #[test]
+    fn seam_check_point_rejects_invalid_arguments() {
+        let seam = new_seam();
+        let mut status = SeamPointStatus::Gap;
+        let mut y = 1.0;
+
+        assert!(!unsafe { seam_check_point(ptr::null(), 50.0, 0, &mut status, &mut y) });
+        assert!(!unsafe { seam_check_point(seam, 50.0, 3, &mut status, &mut y) });
+        assert!(!unsafe { seam_check_point(seam, 50.0, 0, ptr::null_mut(), &mut y) });
+        assert_eq!(status, SeamPointStatus::Gap);
+        assert_eq!(y, 1.0);
+
+        unsafe { seam_free(seam) };
+    }
+
+    #[test]
+    fn range_f32_next_visits_every_float() {
+        let mut range = range_f32_inclusive(-f32::MIN_POSITIVE, next_f32(f32::MIN_POSITIVE));
+        assert_eq!(range_f32_count(range), 5);
+
+        let mut values = Vec::new();
+        let mut x = 0.0;
+        while unsafe { range_f32_next(&mut range, &mut x) } {
+            values.push(x.to_bits());
+        }
+        let expected = [
+            -f32::MIN_POSITIVE,
+            -0.0,
+            0.0,
+            f32::MIN_POSITIVE,
+            next_f32(f32::MIN_POSITIVE),
+        ];
+        assert_eq!(values, expected.map(f32::to_bits));
+        assert_eq!(range_f32_count(range), 0);
+
+        let mut range = range_f32_inclusive(1.0, 2.0);
+        assert!(!unsafe { range_f32_next(ptr::null_mut(), &mut x) });
+        assert!(!unsafe { range_f32_next(&mut range, ptr::null_mut()) });
+        assert_eq!(range, range_f32_inclusive(1.0, 2.0));
+    }
+
+    #[test]
+    fn f32_next_and_prev_skip_denormals() {
+        assert_eq!(f32_next(1.0), f32::from_bits(1.0f32.to_bits() + 1));
+        assert_eq!(f32_prev(1.0), f32::from_bits(1.0f32.to_bits() - 1));
+        assert_eq!(f32_next(0.0), f32::MIN_POSITIVE);
+        assert_eq!(f32_prev(f32::MIN_POSITIVE).to_bits(), 0.0f32.to_bits());
+        assert_eq!(f32_next(-f32::MIN_POSITIVE).to_bits(), (-0.0f32).to_bits());
+        assert_eq!(f32_next(f32::from_bits(1)), f32::MIN_POSITIVE);
+        for x in [-3.5, -0.0, 0.0, 1e-30, 42.0] {
+            assert_eq!(f32_prev(f32_next(x)).to_bits(), f32::to_bits(x));
+        }
+    }
+}
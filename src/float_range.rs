@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use std::iter;
 
 pub fn flush_f32_to_zero(x: f32) -> f32 {
@@ -46,6 +47,7 @@ pub fn prev_f32(x: f32) -> f32 {
     f32::from_bits(result_bits)
 }
 
+/// The number of floats in `[start, end)`, counting 0.0 and -0.0 separately like `next_f32` does.
 pub fn f32s_between(start: f32, end: f32) -> u32 {
     let start = flush_f32_to_zero(start);
     let end = flush_f32_to_zero(end);
@@ -53,26 +55,49 @@ pub fn f32s_between(start: f32, end: f32) -> u32 {
         return 0;
     }
 
-    fn positive_lt(x: u32) -> u32 {
-        if (x & (1 << 31)) == 0 {
-            x - (1 << 23) + 1
-        } else {
-            0
-        }
-    }
-    fn negative_ge(x: u32) -> u32 {
-        positive_lt(x ^ (1 << 31)) + 1
+    // -0.0 is not less than 0.0, so a range that ends at zero excludes both zeros
+    let end_index = if end == 0.0 {
+        f32_index(-0.0)
+    } else {
+        f32_index(end)
+    };
+    (end_index - f32_index(start)) as u32
+}
+
+/// The position of x in the sequence of non-denormal floats, where 0.0 has index 0 and -0.0 has
+/// index -1.
+fn f32_index(x: f32) -> i64 {
+    let bits = flush_f32_to_zero(x).to_bits();
+    let magnitude = bits & !(1 << 31);
+    let positive_index = if magnitude == 0 {
+        0
+    } else {
+        (magnitude - (1 << 23) + 1) as i64
+    };
+    if bits & (1 << 31) == 0 {
+        positive_index
+    } else {
+        -positive_index - 1
     }
+}
 
-    let start_bits = start.to_bits();
-    let end_bits = end.to_bits();
-    let positive = positive_lt(end_bits) - positive_lt(start_bits);
-    let negative = negative_ge(start_bits) - negative_ge(end_bits);
-    positive + negative
+fn f32_from_index(index: i64) -> f32 {
+    let (positive_index, sign) = if index >= 0 {
+        (index as u32, 0)
+    } else {
+        ((-index - 1) as u32, 1 << 31)
+    };
+    let magnitude = if positive_index == 0 {
+        0
+    } else {
+        positive_index - 1 + (1 << 23)
+    };
+    f32::from_bits(magnitude | sign)
 }
 
 /// A closed range of float values.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[repr(C)]
 pub struct RangeF32 {
     pub start: f32,
     pub end: f32,
@@ -98,6 +123,15 @@ impl RangeF32 {
         f32s_between(self.start, self.end) as usize
     }
 
+    /// The nth float in the range, without iterating over the floats before it.
+    pub fn nth(&self, n: usize) -> Option<f32> {
+        if n < self.count() {
+            Some(f32_from_index(f32_index(self.start) + n as i64))
+        } else {
+            None
+        }
+    }
+
     pub fn iter(&self) -> impl Iterator<Item = f32> {
         // Could be done more efficiently by chaining two integer ranges (negative then positive)
         let end = self.end;
@@ -115,3 +149,34 @@ impl RangeF32 {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn count_matches_iter_near_zero() {
+        let min = f32::MIN_POSITIVE;
+        let values = [
+            -next_f32(min),
+            -min,
+            -0.0,
+            0.0,
+            min,
+            next_f32(min),
+            next_f32(next_f32(min)),
+        ];
+
+        for start in values {
+            for end in values {
+                let range = RangeF32::inclusive_exclusive(start, end);
+                let floats: Vec<f32> = range.iter().collect();
+                assert_eq!(range.count(), floats.len(), "{:?}", range);
+                for (n, x) in floats.iter().enumerate() {
+                    assert_eq!(range.nth(n).map(f32::to_bits), Some(x.to_bits()));
+                }
+                assert_eq!(range.nth(floats.len()), None);
+            }
+        }
+    }
+}
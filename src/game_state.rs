@@ -1,5 +1,6 @@
 use crate::{
     geo::{Point3f, Vector3f},
+    palette::Palette,
     process::Process,
 };
 use bytemuck::from_bytes;
@@ -10,6 +11,9 @@ use std::collections::HashMap;
 pub struct Config {
     pub base_addresses: HashMap<String, usize>,
     pub game_versions: Vec<GameVersion>,
+    /// Palettes in addition to the built in ones.
+    #[serde(default)]
+    pub palettes: Vec<Palette>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,16 +28,23 @@ pub struct Globals {
     pub lakitu_state: u32,
     pub surfaces_allocated: u32,
     pub surface_pool: u32,
+    /// The address of `gMarioStates`, or None if Mario shouldn't be shown.
+    #[serde(default)]
+    pub mario_state: Option<u32>,
 }
 
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
 pub struct Surface {
+    pub surface_type: i16,
     pub flags: u8,
+    pub room: i8,
     pub vertex1: [i16; 3],
     pub vertex2: [i16; 3],
     pub vertex3: [i16; 3],
     pub normal: [f32; 3],
     pub origin_offset: f32,
+    /// The address of the object that owns the surface, or 0 for level geometry.
+    pub object: u32,
 }
 
 impl Surface {
@@ -70,65 +81,90 @@ impl Surface {
     }
 }
 
+#[derive(Debug, Clone, Copy)]
+pub struct MarioState {
+    pub pos: [f32; 3],
+    pub vel: [f32; 3],
+    /// The angle Mario is facing, in the game's units of 65536 per turn.
+    pub face_yaw: i16,
+}
+
+impl MarioState {
+    pub fn pos(&self) -> Point3f {
+        Point3f::new(self.pos[0], self.pos[1], self.pos[2])
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct GameState {
+    pub global_timer: u32,
     pub lakitu_pos: [f32; 3],
     pub lakitu_focus: [f32; 3],
+    pub mario: Option<MarioState>,
     pub surfaces: Vec<Surface>,
 }
 
+/// Read the raw bytes of the allocated surfaces in the surface pool.
+pub fn read_surface_pool_bytes(globals: &Globals, process: &Process) -> Vec<u8> {
+    let num_surfaces: u32 = process.read(globals.surfaces_allocated);
+    let surface_pool_addr: u32 = process.read(globals.surface_pool);
+
+    if surface_pool_addr != 0 {
+        process.read_bytes(surface_pool_addr, num_surfaces as usize * 0x30)
+    } else {
+        Vec::new()
+    }
+}
+
 impl GameState {
     pub fn read(globals: &Globals, process: &Process) -> Self {
-        let num_surfaces: u32 = process.read(globals.surfaces_allocated);
-        let surface_pool_addr: u32 = process.read(globals.surface_pool);
-
-        let surfaces = if surface_pool_addr != 0 {
-            let bytes = process.read_bytes(surface_pool_addr, num_surfaces as usize * 0x30);
-
-            bytes
-                .chunks(0x30)
-                .map(|chunk| {
-                    let read_s16 = |offset: usize| {
-                        let offset = if offset % 4 == 0 {
-                            offset + 2
-                        } else {
-                            offset - 2
-                        };
-                        *from_bytes::<i16>(&chunk[offset..offset + 2])
-                    };
-                    let read_f32 = |offset: usize| *from_bytes::<f32>(&chunk[offset..offset + 4]);
-                    let read_s16_3 = |offset: usize| {
-                        [
-                            read_s16(offset + 0),
-                            read_s16(offset + 2),
-                            read_s16(offset + 4),
-                        ]
-                    };
-                    let read_f32_3 = |offset: usize| {
-                        [
-                            read_f32(offset + 0),
-                            read_f32(offset + 4),
-                            read_f32(offset + 8),
-                        ]
+        let surfaces = read_surface_pool_bytes(globals, process)
+            .chunks(0x30)
+            .map(|chunk| {
+                let read_s16 = |offset: usize| {
+                    let offset = if offset.is_multiple_of(4) {
+                        offset + 2
+                    } else {
+                        offset - 2
                     };
+                    *from_bytes::<i16>(&chunk[offset..offset + 2])
+                };
+                let read_f32 = |offset: usize| *from_bytes::<f32>(&chunk[offset..offset + 4]);
+                let read_s16_3 =
+                    |offset: usize| [read_s16(offset), read_s16(offset + 2), read_s16(offset + 4)];
+                let read_f32_3 =
+                    |offset: usize| [read_f32(offset), read_f32(offset + 4), read_f32(offset + 8)];
+
+                Surface {
+                    surface_type: read_s16(0x00),
+                    flags: chunk[0x07],
+                    room: chunk[0x06] as i8,
+                    vertex1: read_s16_3(0x0A),
+                    vertex2: read_s16_3(0x10),
+                    vertex3: read_s16_3(0x16),
+                    normal: read_f32_3(0x1C),
+                    origin_offset: read_f32(0x28),
+                    object: *from_bytes::<u32>(&chunk[0x2C..0x30]),
+                }
+            })
+            .collect();
 
-                    Surface {
-                        flags: chunk[0x07],
-                        vertex1: read_s16_3(0x0A),
-                        vertex2: read_s16_3(0x10),
-                        vertex3: read_s16_3(0x16),
-                        normal: read_f32_3(0x1C),
-                        origin_offset: read_f32(0x28),
-                    }
-                })
-                .collect()
-        } else {
-            Vec::new()
-        };
+        let mario = globals.mario_state.map(|mario_state| {
+            // The yaw is faceAngle[1] at 0x2E, which is the low half of the word at 0x2C since
+            // memory is stored as native endian words
+            let face_angle: u32 = process.read(mario_state + 0x2C);
+            MarioState {
+                pos: process.read(mario_state + 0x3C),
+                vel: process.read(mario_state + 0x48),
+                face_yaw: face_angle as u16 as i16,
+            }
+        });
 
         Self {
+            global_timer: process.read(globals.global_timer),
             lakitu_pos: process.read(globals.lakitu_state + 0x8C),
             lakitu_focus: process.read(globals.lakitu_state + 0x80),
+            mario,
             surfaces,
         }
     }
@@ -3,7 +3,7 @@
 use super::{
     Camera, GameViewScene, SurfaceType, Vertex,
     pipelines::Pipelines,
-    util::{birds_eye_transforms, rotate_transforms, seam_segment_color},
+    util::{birds_eye_transforms, rotate_transforms, surface_color},
 };
 use crate::geo::{Point3f, Vector3f};
 use bytemuck::cast_slice;
@@ -15,10 +15,11 @@ pub struct GameViewSceneBundle<'a> {
     scene: &'a GameViewScene,
     transform_bind_group: wgpu::BindGroup,
     surface_vertex_buffer: (usize, wgpu::Buffer),
-    // hidden_surface_vertex_buffer: (usize, wgpu::Buffer),
-    // wall_hitbox_vertex_buffer: (usize, wgpu::Buffer),
-    // wall_hitbox_outline_vertex_buffer: (usize, wgpu::Buffer),
+    hidden_surface_vertex_buffer: (usize, wgpu::Buffer),
+    wall_hitbox_vertex_buffer: (usize, wgpu::Buffer),
+    wall_hitbox_outline_vertex_buffer: (usize, wgpu::Buffer),
     seam_vertex_buffer: (usize, wgpu::Buffer),
+    mario_vertex_buffer: (usize, wgpu::Buffer),
 }
 
 impl<'a> GameViewSceneBundle<'a> {
@@ -68,35 +69,35 @@ impl<'a> GameViewSceneBundle<'a> {
                 usage: wgpu::BufferUsages::VERTEX,
             }),
         );
-        // let hidden_surface_vertex_buffer = (
-        //     hidden_surface_vertices.len(),
-        //     device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-        //         label: None,
-        //         contents: cast_slice(&hidden_surface_vertices),
-        //         usage: wgpu::BufferUsages::VERTEX,
-        //     }),
-        // );
-
-        // let (wall_hitbox_vertices, wall_hitbox_outline_vertices) =
-        //     get_wall_hitbox_vertices(scene);
-        // let wall_hitbox_vertex_buffer = (
-        //     wall_hitbox_vertices.len(),
-        //     device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-        //         label: None,
-        //         contents: cast_slice(&wall_hitbox_vertices),
-        //         usage: wgpu::BufferUsages::VERTEX,
-        //     }),
-        // );
-        // let wall_hitbox_outline_vertex_buffer = (
-        //     wall_hitbox_outline_vertices.len(),
-        //     device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-        //         label: None,
-        //         contents: cast_slice(&wall_hitbox_outline_vertices),
-        //         usage: wgpu::BufferUsages::VERTEX,
-        //     }),
-        // );
-
-        let seam_vertices = get_seam_vertices(scene);
+        let hidden_surface_vertex_buffer = (
+            hidden_surface_vertices.len(),
+            device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: None,
+                contents: cast_slice(&hidden_surface_vertices),
+                usage: wgpu::BufferUsages::VERTEX,
+            }),
+        );
+
+        let (wall_hitbox_vertices, wall_hitbox_outline_vertices) = get_wall_hitbox_vertices(scene);
+        let wall_hitbox_vertex_buffer = (
+            wall_hitbox_vertices.len(),
+            device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: None,
+                contents: cast_slice(&wall_hitbox_vertices),
+                usage: wgpu::BufferUsages::VERTEX,
+            }),
+        );
+        let wall_hitbox_outline_vertex_buffer = (
+            wall_hitbox_outline_vertices.len(),
+            device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: None,
+                contents: cast_slice(&wall_hitbox_outline_vertices),
+                usage: wgpu::BufferUsages::VERTEX,
+            }),
+        );
+
+        let mut seam_vertices = get_seam_vertices(scene);
+        seam_vertices.extend(get_bookmark_vertices(scene));
         let seam_vertex_buffer = (
             seam_vertices.len(),
             device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
@@ -106,14 +107,25 @@ impl<'a> GameViewSceneBundle<'a> {
             }),
         );
 
+        let mario_vertices = get_mario_vertices(scene);
+        let mario_vertex_buffer = (
+            mario_vertices.len(),
+            device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: None,
+                contents: cast_slice(&mario_vertices),
+                usage: wgpu::BufferUsages::VERTEX,
+            }),
+        );
+
         Self {
             scene,
             transform_bind_group,
             surface_vertex_buffer,
-            // hidden_surface_vertex_buffer,
-            // wall_hitbox_vertex_buffer,
-            // wall_hitbox_outline_vertex_buffer,
+            hidden_surface_vertex_buffer,
+            wall_hitbox_vertex_buffer,
+            wall_hitbox_outline_vertex_buffer,
             seam_vertex_buffer,
+            mario_vertex_buffer,
         }
     }
 
@@ -152,27 +164,30 @@ impl<'a> GameViewSceneBundle<'a> {
         render_pass.set_vertex_buffer(0, self.seam_vertex_buffer.1.slice(..));
         render_pass.draw(0..self.seam_vertex_buffer.0 as u32, 0..1);
 
-        // if scene.wall_hitbox_radius > 0.0 {
-        //     // Render lines first since tris write to z buffer
-        //     render_pass.set_pipeline(&self.wall_hitbox_outline_pipeline);
-        //     render_pass
-        //         .set_vertex_buffer(0, bundle.wall_hitbox_outline_vertex_buffer.1.slice(..));
-        //     render_pass.draw(0..bundle.wall_hitbox_outline_vertex_buffer.0 as u32, 0..1);
-
-        //     // When two wall hitboxes overlap, we should not increase the opacity within
-        //     // their region of overlap (preference).
-        //     // First pass writes only to depth buffer to ensure that only the closest
-        //     // hitbox triangles are drawn, then second pass draws them.
-        //     render_pass.set_vertex_buffer(0, bundle.wall_hitbox_vertex_buffer.1.slice(..));
-        //     render_pass.set_pipeline(&self.wall_hitbox_depth_pass_pipeline);
-        //     render_pass.draw(0..bundle.wall_hitbox_vertex_buffer.0 as u32, 0..1);
-        //     render_pass.set_pipeline(&self.wall_hitbox_pipeline);
-        //     render_pass.draw(0..bundle.wall_hitbox_vertex_buffer.0 as u32, 0..1);
-        // }
-
-        // render_pass.set_pipeline(&self.hidden_surface_pipeline);
-        // render_pass.set_vertex_buffer(0, bundle.hidden_surface_vertex_buffer.1.slice(..));
-        // render_pass.draw(0..bundle.hidden_surface_vertex_buffer.0 as u32, 0..1);
+        render_pass.set_pipeline(&pipelines.grid_line);
+        render_pass.set_vertex_buffer(0, self.mario_vertex_buffer.1.slice(..));
+        render_pass.draw(0..self.mario_vertex_buffer.0 as u32, 0..1);
+
+        if self.scene.wall_hitbox_radius > 0.0 {
+            // Render lines first since tris write to z buffer
+            render_pass.set_pipeline(&pipelines.wall_hitbox_outline);
+            render_pass.set_vertex_buffer(0, self.wall_hitbox_outline_vertex_buffer.1.slice(..));
+            render_pass.draw(0..self.wall_hitbox_outline_vertex_buffer.0 as u32, 0..1);
+
+            // When two wall hitboxes overlap, we should not increase the opacity within
+            // their region of overlap (preference).
+            // First pass writes only to depth buffer to ensure that only the closest
+            // hitbox triangles are drawn, then second pass draws them.
+            render_pass.set_vertex_buffer(0, self.wall_hitbox_vertex_buffer.1.slice(..));
+            render_pass.set_pipeline(&pipelines.wall_hitbox_depth_pass);
+            render_pass.draw(0..self.wall_hitbox_vertex_buffer.0 as u32, 0..1);
+            render_pass.set_pipeline(&pipelines.wall_hitbox);
+            render_pass.draw(0..self.wall_hitbox_vertex_buffer.0 as u32, 0..1);
+        }
+
+        render_pass.set_pipeline(&pipelines.hidden_surface);
+        render_pass.set_vertex_buffer(0, self.hidden_surface_vertex_buffer.1.slice(..));
+        render_pass.draw(0..self.hidden_surface_vertex_buffer.0 as u32, 0..1);
     }
 }
 
@@ -184,19 +199,14 @@ fn get_surface_vertices(scene: &GameViewScene) -> (Vec<Vertex>, Vec<Vertex>) {
         let hidden = scene.hidden_surfaces.contains(&i);
         let hovered = scene.hovered_surface == Some(i);
 
-        let mut color = match surface.ty {
-            SurfaceType::Floor => [0.5, 0.5, 1.0, 1.0],
-            SurfaceType::Ceiling => [1.0, 0.5, 0.5, 1.0],
-            SurfaceType::WallXProj => [0.3, 0.8, 0.3, 1.0],
-            SurfaceType::WallZProj => [0.15, 0.4, 0.15, 1.0],
-        };
+        let mut color = surface_color(surface.ty);
 
         if hidden {
             let scale = 1.5;
             color[0] *= scale;
             color[1] *= scale;
             color[2] *= scale;
-            color[3] = if hovered { 0.1 } else { 0.0 };
+            color[3] = if hovered { 0.2 } else { 0.08 };
         }
 
         if hovered {
@@ -227,6 +237,10 @@ fn get_wall_hitbox_vertices(scene: &GameViewScene) -> (Vec<Vertex>, Vec<Vertex>)
     let mut wall_hitbox_vertices: Vec<Vertex> = Vec::new();
     let mut wall_hitbox_outline_vertices: Vec<Vertex> = Vec::new();
 
+    if scene.wall_hitbox_radius <= 0.0 {
+        return (wall_hitbox_vertices, wall_hitbox_outline_vertices);
+    }
+
     for (i, surface) in scene.surfaces.iter().enumerate() {
         if scene.hidden_surfaces.contains(&i) {
             continue;
@@ -286,7 +300,7 @@ fn get_wall_hitbox_vertices(scene: &GameViewScene) -> (Vec<Vertex>, Vec<Vertex>)
 
         let camera_dist = match &scene.camera {
             Camera::Rotate(camera) => distance(&int_vertices[0], &Point3f::from_slice(&camera.pos)),
-            Camera::BirdsEye(camera) => 1000.0,
+            Camera::BirdsEye(camera) => camera.span_y,
         };
 
         for i0 in 0..3 {
@@ -345,9 +359,13 @@ fn get_seam_vertices(scene: &GameViewScene) -> Vec<Vertex> {
             let perp_dir_1 = Vector3f::y().cross(&seam_dir);
             let perp_dir_2 = seam_dir.cross(&perp_dir_1);
 
-            let color = seam_segment_color(segment.status);
+            let color = segment
+                .custom_color
+                .unwrap_or_else(|| scene.palette.segment_color(segment.status));
 
-            let radius = if scene.hovered_seam.as_ref() == Some(&seam.seam) {
+            let radius = if scene.hovered_seam.as_ref() == Some(&seam.seam)
+                || scene.selected_seam.as_ref() == Some(&seam.seam)
+            {
                 10.0
             } else {
                 5.0
@@ -380,3 +398,84 @@ fn get_seam_vertices(scene: &GameViewScene) -> Vec<Vertex> {
 
     vertices
 }
+
+/// Bookmarks are drawn as octahedrons around the bookmarked point.
+fn get_bookmark_vertices(scene: &GameViewScene) -> Vec<Vertex> {
+    let mut vertices = Vec::new();
+    let radius = 15.0;
+    let axes = [Vector3f::x(), Vector3f::y(), Vector3f::z()];
+    let color = scene.palette.bookmark_color();
+
+    for &pos in &scene.bookmarks {
+        for &sx in &[-1.0, 1.0] {
+            for &sy in &[-1.0, 1.0] {
+                for &sz in &[-1.0, 1.0] {
+                    vertices.extend(&[
+                        Vertex::new(pos + sx * radius * axes[0], color),
+                        Vertex::new(pos + sy * radius * axes[1], color),
+                        Vertex::new(pos + sz * radius * axes[2], color),
+                    ]);
+                }
+            }
+        }
+    }
+
+    vertices
+}
+
+/// Mario is drawn as a wireframe of his hitbox cylinder with a line in the direction he is
+/// facing. His trail is a line through his recent positions with a small cross on each frame.
+/// These are line list vertices.
+fn get_mario_vertices(scene: &GameViewScene) -> Vec<Vertex> {
+    let mut vertices = Vec::new();
+    let radius = 37.0;
+    let height = 160.0;
+    let num_sides = 16;
+    let color = scene.palette.mario_color();
+
+    if let Some(mario) = &scene.mario {
+        let offset = |angle: f32| radius * Vector3f::new(angle.cos(), 0.0, angle.sin());
+        for i in 0..num_sides {
+            let a0 = (i as f32 / num_sides as f32) * 2.0 * PI;
+            let a1 = ((i + 1) as f32 / num_sides as f32) * 2.0 * PI;
+            for y in [0.0, height] {
+                let base = mario.pos + y * Vector3f::y();
+                vertices.extend(&[
+                    Vertex::new(base + offset(a0), color),
+                    Vertex::new(base + offset(a1), color),
+                ]);
+            }
+            if i % 4 == 0 {
+                vertices.extend(&[
+                    Vertex::new(mario.pos + offset(a0), color),
+                    Vertex::new(mario.pos + offset(a0) + height * Vector3f::y(), color),
+                ]);
+            }
+        }
+
+        let center = mario.pos + height / 2.0 * Vector3f::y();
+        vertices.extend(&[
+            Vertex::new(center, color),
+            Vertex::new(center + 2.0 * radius * mario.face_dir, color),
+        ]);
+    }
+
+    let trail_color = scene.palette.mario_trail_color();
+    for pair in scene.mario_trail.windows(2) {
+        vertices.extend(&[
+            Vertex::new(pair[0], trail_color),
+            Vertex::new(pair[1], trail_color),
+        ]);
+    }
+    let marker_size = 5.0;
+    for &pos in &scene.mario_trail {
+        for axis in [Vector3f::x(), Vector3f::y(), Vector3f::z()] {
+            vertices.extend(&[
+                Vertex::new(pos - marker_size * axis, trail_color),
+                Vertex::new(pos + marker_size * axis, trail_color),
+            ]);
+        }
+    }
+
+    vertices
+}
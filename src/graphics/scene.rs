@@ -1,6 +1,7 @@
 use crate::{
     edge::ProjectedPoint,
     geo::{Point3f, Vector3f},
+    palette::Palette,
     seam::{PointStatus, RangeStatus, Seam},
 };
 use nalgebra::{Point3, Vector3};
@@ -22,6 +23,20 @@ pub struct GameViewScene {
     pub hidden_surfaces: HashSet<usize>,
     pub seams: Vec<SeamInfo>,
     pub hovered_seam: Option<Seam>,
+    /// The seam open in the seam view, which is highlighted like the hovered seam.
+    pub selected_seam: Option<Seam>,
+    pub bookmarks: Vec<Point3f>,
+    pub palette: Palette,
+    pub mario: Option<MarioInfo>,
+    /// Mario's positions on recent frames, oldest first.
+    pub mario_trail: Vec<Point3f>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MarioInfo {
+    pub pos: Point3f,
+    /// A horizontal unit vector in the direction Mario is facing.
+    pub face_dir: Vector3f,
 }
 
 #[derive(Debug, Clone)]
@@ -31,6 +46,21 @@ pub struct SeamViewScene {
     pub seam: FocusedSeamInfo,
     pub vertical_grid_lines: Vec<Point3<f64>>,
     pub horizontal_grid_lines: Vec<Point3<f64>>,
+    pub overlay_points: Vec<OverlayPoint>,
+    pub bookmarks: Vec<Point3f>,
+    pub palette: Palette,
+    /// Mario's position, if he is close to the seam.
+    pub mario: Option<Point3f>,
+    /// Mario's positions on recent frames that were close to the seam, oldest first.
+    pub mario_trail: Vec<Point3f>,
+}
+
+/// A point loaded from a previous export, drawn behind the live points.
+#[derive(Debug, Clone)]
+pub struct OverlayPoint {
+    pub pos: Point3f,
+    /// True if the live computation gives a different status or y value.
+    pub differs: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -41,7 +71,6 @@ pub struct Viewport {
     pub height: f32,
 }
 
-#[allow(unused)]
 #[derive(Debug, Clone)]
 pub enum Camera {
     Rotate(RotateCamera),
@@ -120,6 +149,8 @@ pub struct SeamSegment {
     pub proj_endpoint1: ProjectedPoint<f32>,
     pub proj_endpoint2: ProjectedPoint<f32>,
     pub status: RangeStatus,
+    /// Overrides the color for `status`, e.g. when set by a script.
+    pub custom_color: Option<[f32; 4]>,
 }
 
 impl SeamSegment {
@@ -1,6 +1,6 @@
 use super::{
-    FocusedSeamData, SeamSegment, SeamViewScene, Vertex, pipelines::Pipelines, seam_point_color,
-    seam_view_world_to_screen, upload_vertex_buffer, util::seam_segment_color,
+    FocusedSeamData, SeamSegment, SeamViewScene, Vertex, pipelines::Pipelines,
+    seam_view_world_to_screen, upload_vertex_buffer,
 };
 use crate::{
     geo::{Matrix4f, Point3f, Vector3f, point_f32_to_f64},
@@ -15,6 +15,9 @@ pub struct SeamViewSceneBundle<'a> {
     transform_bind_group: wgpu::BindGroup,
     seam_segment_vertex_buffer: (usize, wgpu::Buffer),
     seam_point_vertex_buffer: (usize, wgpu::Buffer),
+    overlay_point_vertex_buffer: (usize, wgpu::Buffer),
+    bookmark_vertex_buffer: (usize, wgpu::Buffer),
+    mario_vertex_buffer: (usize, wgpu::Buffer),
     grid_line_vertex_buffer: (usize, wgpu::Buffer),
 }
 
@@ -65,6 +68,15 @@ impl<'a> SeamViewSceneBundle<'a> {
         };
         let seam_point_vertex_buffer = upload_vertex_buffer(device, &seam_point_vertices);
 
+        let overlay_point_vertices = get_overlay_point_vertices(scene);
+        let overlay_point_vertex_buffer = upload_vertex_buffer(device, &overlay_point_vertices);
+
+        let bookmark_vertices = get_bookmark_vertices(scene);
+        let bookmark_vertex_buffer = upload_vertex_buffer(device, &bookmark_vertices);
+
+        let mario_vertices = get_mario_vertices(scene);
+        let mario_vertex_buffer = upload_vertex_buffer(device, &mario_vertices);
+
         let grid_line_vertices = get_grid_line_vertices(scene);
         let grid_line_vertex_buffer = upload_vertex_buffer(device, &grid_line_vertices);
 
@@ -73,6 +85,9 @@ impl<'a> SeamViewSceneBundle<'a> {
             transform_bind_group,
             seam_segment_vertex_buffer,
             seam_point_vertex_buffer,
+            overlay_point_vertex_buffer,
+            bookmark_vertex_buffer,
+            mario_vertex_buffer,
             grid_line_vertex_buffer,
         }
     }
@@ -112,9 +127,21 @@ impl<'a> SeamViewSceneBundle<'a> {
         render_pass.set_vertex_buffer(0, self.seam_segment_vertex_buffer.1.slice(..));
         render_pass.draw(0..self.seam_segment_vertex_buffer.0 as u32, 0..1);
 
+        render_pass.set_pipeline(&pipelines.seam);
+        render_pass.set_vertex_buffer(0, self.overlay_point_vertex_buffer.1.slice(..));
+        render_pass.draw(0..self.overlay_point_vertex_buffer.0 as u32, 0..1);
+
         render_pass.set_pipeline(&pipelines.seam);
         render_pass.set_vertex_buffer(0, self.seam_point_vertex_buffer.1.slice(..));
         render_pass.draw(0..self.seam_point_vertex_buffer.0 as u32, 0..1);
+
+        render_pass.set_pipeline(&pipelines.seam);
+        render_pass.set_vertex_buffer(0, self.bookmark_vertex_buffer.1.slice(..));
+        render_pass.draw(0..self.bookmark_vertex_buffer.0 as u32, 0..1);
+
+        render_pass.set_pipeline(&pipelines.seam);
+        render_pass.set_vertex_buffer(0, self.mario_vertex_buffer.1.slice(..));
+        render_pass.draw(0..self.mario_vertex_buffer.0 as u32, 0..1);
     }
 }
 
@@ -132,7 +159,7 @@ fn get_seam_segment_vertices(scene: &SeamViewScene, segments: &[SeamSegment]) ->
     };
 
     for segment in segments {
-        let color = seam_segment_color(segment.status);
+        let color = scene.palette.segment_color(segment.status);
 
         let endpoint1 = point_f32_to_f64(segment.endpoint1());
         let endpoint2 = point_f32_to_f64(segment.endpoint2());
@@ -158,31 +185,119 @@ fn get_seam_point_vertices(
 ) -> Vec<Vertex> {
     let mut vertices = Vec::new();
 
-    let radius = 0.015;
+    for (world_pos, status) in points {
+        push_point_vertices(
+            &mut vertices,
+            scene,
+            *world_pos,
+            0.015,
+            scene.palette.point_color(*status),
+        );
+    }
+
+    vertices
+}
+
+/// Overlay points are drawn larger than live points so that they show up as an outline.
+fn get_overlay_point_vertices(scene: &SeamViewScene) -> Vec<Vertex> {
+    let mut vertices = Vec::new();
+
+    for point in &scene.overlay_points {
+        let radius = if point.differs { 0.03 } else { 0.022 };
+        push_point_vertices(
+            &mut vertices,
+            scene,
+            point.pos,
+            radius,
+            scene.palette.exported_point_color(point.differs),
+        );
+    }
+
+    vertices
+}
+
+/// Bookmarks are drawn as diamonds that stay the same size on screen.
+fn get_bookmark_vertices(scene: &SeamViewScene) -> Vec<Vertex> {
+    let mut vertices = Vec::new();
+    let radius = 0.03;
     let y_offset = radius * Vector3f::y();
     let x_offset = radius * Vector3f::x() * scene.viewport.height / scene.viewport.width;
+    let color = scene.palette.bookmark_color();
 
-    for (world_pos, status) in points {
-        let color = seam_point_color(*status);
-
+    for &world_pos in &scene.bookmarks {
         let screen_pos =
-            seam_view_world_to_screen(&scene.camera, &scene.viewport, point_f32_to_f64(*world_pos));
-
+            seam_view_world_to_screen(&scene.camera, &scene.viewport, point_f32_to_f64(world_pos));
         vertices.extend(&[
-            Vertex::new(screen_pos - x_offset - y_offset, color),
-            Vertex::new(screen_pos + x_offset - y_offset, color),
-            Vertex::new(screen_pos - x_offset + y_offset, color),
+            Vertex::new(screen_pos - x_offset, color),
+            Vertex::new(screen_pos - y_offset, color),
+            Vertex::new(screen_pos + x_offset, color),
         ]);
         vertices.extend(&[
+            Vertex::new(screen_pos - x_offset, color),
+            Vertex::new(screen_pos + y_offset, color),
+            Vertex::new(screen_pos + x_offset, color),
+        ]);
+    }
+
+    vertices
+}
+
+/// Mario is drawn as a triangle centered on his position, and his recent positions as small squares.
+fn get_mario_vertices(scene: &SeamViewScene) -> Vec<Vertex> {
+    let mut vertices = Vec::new();
+
+    for &world_pos in &scene.mario_trail {
+        push_point_vertices(
+            &mut vertices,
+            scene,
+            world_pos,
+            0.008,
+            scene.palette.mario_trail_color(),
+        );
+    }
+
+    if let Some(world_pos) = scene.mario {
+        let radius = 0.04;
+        let y_offset = radius * Vector3f::y();
+        let x_offset = radius * Vector3f::x() * scene.viewport.height / scene.viewport.width;
+        let screen_pos =
+            seam_view_world_to_screen(&scene.camera, &scene.viewport, point_f32_to_f64(world_pos));
+        let color = scene.palette.mario_color();
+        vertices.extend(&[
+            Vertex::new(screen_pos - x_offset - y_offset, color),
             Vertex::new(screen_pos + x_offset - y_offset, color),
-            Vertex::new(screen_pos - x_offset + y_offset, color),
-            Vertex::new(screen_pos + x_offset + y_offset, color),
+            Vertex::new(screen_pos + y_offset, color),
         ]);
     }
 
     vertices
 }
 
+fn push_point_vertices(
+    vertices: &mut Vec<Vertex>,
+    scene: &SeamViewScene,
+    world_pos: Point3f,
+    radius: f32,
+    color: [f32; 4],
+) {
+    let y_offset = radius * Vector3f::y();
+    let x_offset = radius * Vector3f::x() * scene.viewport.height / scene.viewport.width;
+
+    let screen_pos =
+        seam_view_world_to_screen(&scene.camera, &scene.viewport, point_f32_to_f64(world_pos));
+
+    vertices.extend(&[
+        Vertex::new(screen_pos - x_offset - y_offset, color),
+        Vertex::new(screen_pos + x_offset - y_offset, color),
+        Vertex::new(screen_pos - x_offset + y_offset, color),
+    ]);
+    vertices.extend(&[
+        Vertex::new(screen_pos + x_offset - y_offset, color),
+        Vertex::new(screen_pos - x_offset + y_offset, color),
+        Vertex::new(screen_pos + x_offset + y_offset, color),
+    ]);
+}
+
 fn get_grid_line_vertices(scene: &SeamViewScene) -> Vec<Vertex> {
     let mut vertices = Vec::new();
     let color = [0.4, 0.4, 0.4, 1.0];
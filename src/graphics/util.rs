@@ -1,8 +1,5 @@
-use super::{BirdsEyeCamera, RotateCamera, SeamViewCamera, Viewport};
-use crate::{
-    geo::{Matrix4f, Point3f, Vector3f, Vector4f, direction_to_pitch_yaw},
-    seam::{PointStatus, RangeStatus},
-};
+use super::{BirdsEyeCamera, RotateCamera, SeamViewCamera, SurfaceType, Viewport};
+use crate::geo::{Matrix4f, Point3f, Vector3f, Vector4f, direction_to_pitch_yaw};
 use bytemuck::{Pod, cast_slice};
 use nalgebra::{Point3, Vector3, distance};
 use std::f32::consts::PI;
@@ -73,34 +70,12 @@ pub fn seam_view_screen_to_world(
         + (point[0] as f64) * (span_h / 2.0) * camera.right_dir
 }
 
-pub fn seam_point_color(status: PointStatus) -> [f32; 4] {
-    match status {
-        PointStatus::Gap => [0.0, 1.0, 0.0, 1.0],
-        PointStatus::Overlap => [0.1, 0.1, 1.0, 1.0],
-        PointStatus::None => [1.0, 1.0, 1.0, 1.0],
-    }
-}
-
-pub fn seam_segment_color(status: RangeStatus) -> [f32; 4] {
-    match status {
-        RangeStatus::Checked {
-            has_gap: false,
-            has_overlap: false,
-        } => [1.0, 1.0, 1.0, 1.0],
-        RangeStatus::Checked {
-            has_gap: true,
-            has_overlap: false,
-        } => [0.0, 1.0, 0.0, 1.0],
-        RangeStatus::Checked {
-            has_gap: false,
-            has_overlap: true,
-        } => [0.0, 0.0, 1.0, 1.0],
-        RangeStatus::Checked {
-            has_gap: true,
-            has_overlap: true,
-        } => [0.0, 1.0, 1.0, 1.0],
-        RangeStatus::Unchecked => [0.1, 0.1, 0.1, 1.0],
-        RangeStatus::Skipped => [1.0, 0.0, 0.0, 1.0],
+pub fn surface_color(ty: SurfaceType) -> [f32; 4] {
+    match ty {
+        SurfaceType::Floor => [0.5, 0.5, 1.0, 1.0],
+        SurfaceType::Ceiling => [1.0, 0.5, 0.5, 1.0],
+        SurfaceType::WallXProj => [0.3, 0.8, 0.3, 1.0],
+        SurfaceType::WallZProj => [0.15, 0.4, 0.15, 1.0],
     }
 }
 
@@ -0,0 +1,8 @@
+//! Seam geometry and gap/overlap detection, shared by the GUI and the C API in [`ffi`].
+
+pub mod edge;
+pub mod export;
+pub mod ffi;
+pub mod float_range;
+pub mod geo;
+pub mod seam;
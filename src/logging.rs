@@ -0,0 +1,106 @@
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{self, Write},
+    sync::{Mutex, OnceLock},
+    time::{Duration, Instant},
+};
+
+pub const LOG_FILE: &str = "log.txt";
+
+/// The number of records kept for the log window.
+const MAX_RECENT_RECORDS: usize = 1000;
+
+#[derive(Debug, Clone)]
+pub struct LogRecord {
+    /// The time since the program started.
+    pub time: Duration,
+    pub level: Level,
+    pub target: String,
+    pub message: String,
+}
+
+impl LogRecord {
+    /// Format the record as `[hh:mm:ss.SSS] LEVEL [target] message`.
+    pub fn line(&self) -> String {
+        let seconds = self.time.as_secs();
+        format!(
+            "[{:02}:{:02}:{:02}.{:03}] {:<5} [{}] {}",
+            seconds / 3600,
+            (seconds / 60) % 60,
+            seconds % 60,
+            self.time.subsec_millis(),
+            self.level,
+            self.target,
+            self.message
+        )
+    }
+}
+
+/// A logger that writes to `LOG_FILE` and keeps the most recent records in memory so that they
+/// can be shown in the UI.
+struct Logger {
+    start_time: Instant,
+    file: Mutex<File>,
+    recent: Mutex<VecDeque<LogRecord>>,
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= Level::Info
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let record = LogRecord {
+            time: self.start_time.elapsed(),
+            level: record.level(),
+            target: record.target().to_owned(),
+            message: record.args().to_string(),
+        };
+
+        let _ = writeln!(self.file.lock().unwrap(), "{}", record.line());
+
+        let mut recent = self.recent.lock().unwrap();
+        if recent.len() >= MAX_RECENT_RECORDS {
+            recent.pop_front();
+        }
+        recent.push_back(record);
+    }
+
+    fn flush(&self) {
+        let _ = self.file.lock().unwrap().flush();
+    }
+}
+
+static LOGGER: OnceLock<Logger> = OnceLock::new();
+
+pub fn init() -> io::Result<()> {
+    let logger = Logger {
+        start_time: Instant::now(),
+        file: Mutex::new(File::create(LOG_FILE)?),
+        recent: Mutex::new(VecDeque::new()),
+    };
+    let logger = LOGGER.get_or_init(|| logger);
+    log::set_logger(logger).map_err(|error| io::Error::other(error.to_string()))?;
+    log::set_max_level(LevelFilter::Info);
+    Ok(())
+}
+
+/// The most recent log records, oldest first.
+pub fn recent_records() -> Vec<LogRecord> {
+    LOGGER
+        .get()
+        .map(|logger| logger.recent.lock().unwrap().iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+pub fn clear_recent_records() {
+    if let Some(logger) = LOGGER.get() {
+        logger.recent.lock().unwrap().clear();
+    }
+}
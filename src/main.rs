@@ -3,8 +3,9 @@
 use graphics::{ImguiRenderer, Renderer};
 use imgui::{ConfigFlags, Context};
 use imgui_winit_support::{HiDpiMode, WinitPlatform};
-use log::LevelFilter;
 use model::App;
+use settings::{SETTINGS_FILE, Settings};
+use sm64_seam_tool::{edge, export, float_range, geo, seam};
 use std::time::{Duration, Instant};
 use ui::render_app;
 use winit::{
@@ -13,22 +14,33 @@ use winit::{
     window::WindowBuilder,
 };
 
-mod edge;
-mod float_range;
+mod bookmarks;
+mod diagnostics;
 mod game_state;
-mod geo;
 mod graphics;
+mod logging;
+mod mesh_export;
 mod model;
+mod palette;
 mod process;
-mod seam;
+mod report;
+mod scripting;
+mod seam_list;
 mod seam_processor;
+mod server;
+mod settings;
 mod spatial_partition;
 mod ui;
 mod util;
 
 fn main() {
     log_panics::init();
-    simple_logging::log_to_file("log.txt", LevelFilter::Info).unwrap();
+    logging::init().unwrap();
+
+    let settings = Settings::load(SETTINGS_FILE).unwrap_or_else(|error| {
+        log::error!("Failed to load {}: {}", SETTINGS_FILE, error);
+        Settings::default()
+    });
 
     futures::executor::block_on(async {
         let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
@@ -43,14 +55,16 @@ fn main() {
             .max()
             .unwrap_or_default();
 
-        let window = WindowBuilder::new()
+        let mut window_builder = WindowBuilder::new()
             .with_title("Don't let your seams be seams")
             .with_max_inner_size(winit::dpi::PhysicalSize::new(
                 max_screen_dim,
                 max_screen_dim,
-            ))
-            .build(&event_loop)
-            .unwrap();
+            ));
+        if let Some([width, height]) = settings.window_size {
+            window_builder = window_builder.with_inner_size(winit::dpi::PhysicalSize::new(width, height));
+        }
+        let window = window_builder.build(&event_loop).unwrap();
 
         let surface = unsafe { instance.create_surface(&window).unwrap() };
         let adapter = instance
@@ -93,6 +107,7 @@ fn main() {
 
         let mut imgui = Context::create();
         imgui.set_ini_filename(None);
+        imgui.load_ini_settings(&settings.window_layout);
         imgui.style_mut().window_rounding = 0.0;
         imgui.style_mut().colors[imgui::StyleColor::WindowBg as usize] = [0.0, 0.0, 0.0, 0.0];
         imgui.io_mut().config_flags |= ConfigFlags::NO_MOUSE_CURSOR_CHANGE;
@@ -103,7 +118,7 @@ fn main() {
         let imgui_renderer = ImguiRenderer::new(&mut imgui, &device, &queue, surface_config.format);
 
         let mut renderer = Renderer::new(&device, surface_config.format);
-        let mut app = App::new();
+        let mut app = App::new(settings);
 
         let mut last_fps_time = Instant::now();
         let mut frames_since_fps = 0;
@@ -126,7 +141,18 @@ fn main() {
             platform.handle_event(imgui.io_mut(), &window, &event);
             match event {
                 Event::WindowEvent { event, .. } => match event {
-                    WindowEvent::CloseRequested => elwt.exit(),
+                    WindowEvent::CloseRequested => {
+                        let settings = app.settings_mut();
+                        if !settings.reset_layout {
+                            settings.window_size = Some([window.inner_size().width, window.inner_size().height]);
+                            settings.window_layout.clear();
+                            imgui.save_ini_settings(&mut settings.window_layout);
+                        }
+                        if let Err(error) = settings.save(SETTINGS_FILE) {
+                            log::error!("Failed to save {}: {}", SETTINGS_FILE, error);
+                        }
+                        elwt.exit();
+                    }
                     WindowEvent::Resized(size) => {
                         surface_config.width = size.width;
                         surface_config.height = size.height;
@@ -0,0 +1,145 @@
+use crate::graphics::{GameViewScene, surface_color};
+use std::{
+    fmt::{self, Display},
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+pub const DEFAULT_MESH_NAME: &str = "area";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MeshFormat {
+    Ply,
+    Obj,
+}
+
+impl MeshFormat {
+    pub fn all() -> Vec<Self> {
+        vec![Self::Ply, Self::Obj]
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            MeshFormat::Ply => "ply",
+            MeshFormat::Obj => "obj",
+        }
+    }
+}
+
+impl Display for MeshFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, ".{}", self.extension())
+    }
+}
+
+/// Triangles for the surfaces and line segments for the seams, with a color for each vertex.
+#[derive(Debug, Default)]
+struct Mesh {
+    vertices: Vec<([f32; 3], [f32; 4])>,
+    faces: Vec<[usize; 3]>,
+    edges: Vec<[usize; 2]>,
+}
+
+impl Mesh {
+    fn from_scene(scene: &GameViewScene) -> Self {
+        let mut mesh = Self::default();
+
+        for (i, surface) in scene.surfaces.iter().enumerate() {
+            if scene.hidden_surfaces.contains(&i) {
+                continue;
+            }
+            let color = surface_color(surface.ty);
+            let start = mesh.vertices.len();
+            for vertex in surface.vertices {
+                mesh.vertices.push((vertex, color));
+            }
+            mesh.faces.push([start, start + 1, start + 2]);
+        }
+
+        for segment in scene.seams.iter().flat_map(|seam| &seam.segments) {
+            let color = segment
+                .custom_color
+                .unwrap_or_else(|| scene.palette.segment_color(segment.status));
+            let start = mesh.vertices.len();
+            mesh.vertices.push((segment.endpoint1, color));
+            mesh.vertices.push((segment.endpoint2, color));
+            mesh.edges.push([start, start + 1]);
+        }
+
+        mesh
+    }
+
+    fn write_ply(&self, writer: &mut impl Write) -> io::Result<()> {
+        writeln!(writer, "ply")?;
+        writeln!(writer, "format ascii 1.0")?;
+        writeln!(writer, "comment exported by sm64-seam-tool")?;
+        writeln!(writer, "element vertex {}", self.vertices.len())?;
+        writeln!(writer, "property float x")?;
+        writeln!(writer, "property float y")?;
+        writeln!(writer, "property float z")?;
+        writeln!(writer, "property uchar red")?;
+        writeln!(writer, "property uchar green")?;
+        writeln!(writer, "property uchar blue")?;
+        writeln!(writer, "element face {}", self.faces.len())?;
+        writeln!(writer, "property list uchar int vertex_indices")?;
+        writeln!(writer, "element edge {}", self.edges.len())?;
+        writeln!(writer, "property int vertex1")?;
+        writeln!(writer, "property int vertex2")?;
+        writeln!(writer, "end_header")?;
+
+        let to_u8 = |c: f32| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+        for ([x, y, z], color) in &self.vertices {
+            writeln!(
+                writer,
+                "{} {} {} {} {} {}",
+                x,
+                y,
+                z,
+                to_u8(color[0]),
+                to_u8(color[1]),
+                to_u8(color[2]),
+            )?;
+        }
+        for [a, b, c] in &self.faces {
+            writeln!(writer, "3 {} {} {}", a, b, c)?;
+        }
+        for [a, b] in &self.edges {
+            writeln!(writer, "{} {}", a, b)?;
+        }
+        Ok(())
+    }
+
+    /// Vertex colors are written after the position, which is supported by Blender and MeshLab.
+    fn write_obj(&self, writer: &mut impl Write) -> io::Result<()> {
+        writeln!(writer, "# exported by sm64-seam-tool")?;
+        for ([x, y, z], [r, g, b, _]) in &self.vertices {
+            writeln!(writer, "v {} {} {} {} {} {}", x, y, z, r, g, b)?;
+        }
+        writeln!(writer, "o surfaces")?;
+        for [a, b, c] in &self.faces {
+            writeln!(writer, "f {} {} {}", a + 1, b + 1, c + 1)?;
+        }
+        writeln!(writer, "o seams")?;
+        for [a, b] in &self.edges {
+            writeln!(writer, "l {} {}", a + 1, b + 1)?;
+        }
+        Ok(())
+    }
+}
+
+/// Save the surfaces and seam segments shown in the game view as a 3D model, using the same
+/// colors as the game view.
+pub fn save_scene_mesh(
+    path: impl AsRef<Path>,
+    format: MeshFormat,
+    scene: &GameViewScene,
+) -> io::Result<()> {
+    let mesh = Mesh::from_scene(scene);
+    let mut writer = BufWriter::new(File::create(path)?);
+    match format {
+        MeshFormat::Ply => mesh.write_ply(&mut writer)?,
+        MeshFormat::Obj => mesh.write_obj(&mut writer)?,
+    }
+    writer.flush()
+}
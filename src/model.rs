@@ -1,24 +1,58 @@
 use crate::{
-    float_range::prev_f32,
-    game_state::{Config, Globals},
-    geo::point_f32_to_f64,
+    bookmarks::{BOOKMARKS_FILE, Bookmarks},
+    diagnostics::CONFIG_FILE,
+    edge::{ProjectedPoint, ProjectionAxis},
+    export::{
+        Compression, ExportCheckpoint, ExportEstimate, ExportFormat, ExportOptions, ExportProgress,
+        ExportedPoint, available_space, estimate_export_size, load_checkpoint,
+    },
+    float_range::{RangeF32, prev_f32},
+    game_state::{Config, GameState, Globals, Surface},
+    geo::{Point3f, Vector3f, pitch_yaw_to_direction, point_f32_to_f64},
+    graphics::BirdsEyeCamera,
+    mesh_export::{DEFAULT_MESH_NAME, MeshFormat},
+    palette::Palette,
     process::Process,
+    report::DEFAULT_REPORT_NAME,
+    scripting::ScriptHost,
     seam::PointStatusFilter,
-    seam::{PointFilter, Seam},
+    seam::{PointFilter, PointStatus, Seam},
     seam_processor::SeamProcessor,
+    server::Server,
+    settings::{ExportDefaults, Settings},
 };
+use log::Level;
 use nalgebra::Point3;
-use std::{fs, sync::Arc, sync::Mutex};
+use std::{
+    collections::{HashSet, VecDeque},
+    fmt::{self, Display},
+    fs, io, mem,
+    sync::Arc,
+    sync::Mutex,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    thread::JoinHandle,
+};
 use sysinfo::System;
 
+/// The height of the birds-eye camera, which must be above all geometry.
+pub const BIRDS_EYE_CAMERA_Y: f32 = 9000.0;
+pub const DEFAULT_BIRDS_EYE_SPAN: f32 = 4000.0;
+
 pub enum App {
     ConnectionMenu(Box<ConnectionMenu>),
     Connected(ConnectedView),
 }
 
 impl App {
-    pub fn new() -> Self {
-        Self::ConnectionMenu(Box::from(ConnectionMenu::new()))
+    pub fn new(settings: Settings) -> Self {
+        Self::ConnectionMenu(Box::from(ConnectionMenu::new(settings)))
+    }
+
+    pub fn settings_mut(&mut self) -> &mut Settings {
+        match self {
+            App::ConnectionMenu(menu) => &mut menu.settings,
+            App::Connected(view) => &mut view.settings,
+        }
     }
 }
 
@@ -30,19 +64,26 @@ pub struct ConnectionMenu {
     pub base_addr_buffer: String,
     pub selected_base_addr: Option<usize>,
     pub selected_version_index: usize,
+    pub settings: Settings,
 }
 
 impl ConnectionMenu {
-    pub fn new() -> Self {
-        let config_text = fs::read_to_string("config.json").unwrap();
-        let config = json5::from_str(&config_text).unwrap();
+    pub fn new(settings: Settings) -> Self {
+        let config_text = fs::read_to_string(CONFIG_FILE).unwrap();
+        let config: Config = json5::from_str(&config_text).unwrap();
+        let selected_version_index = config
+            .game_versions
+            .iter()
+            .position(|version| Some(&version.name) == settings.game_version.as_ref())
+            .unwrap_or(0);
         Self {
             config,
             system: System::new(),
             selected_pid: None,
             base_addr_buffer: String::with_capacity(32),
             selected_base_addr: None,
-            selected_version_index: 0,
+            selected_version_index,
+            settings,
         }
     }
 }
@@ -53,50 +94,483 @@ pub struct ConnectedView {
     pub sync_to_game: bool,
     pub seam_processor: SeamProcessor,
     pub hovered_seam: Option<Seam>,
-    pub seam_view: Option<SeamViewState>,
+    /// The open seam views, shown as tabs.
+    pub seam_views: Vec<SeamViewState>,
+    pub active_seam_view: usize,
+    /// Set when a seam view is opened so that its tab is selected on the next frame.
+    pub select_seam_view_tab: bool,
+    pub seam_views_side_by_side: bool,
+    pub lock_seam_view_cameras: bool,
+    /// The camera of the last seam view that moved, applied to the others while the cameras are
+    /// locked.
+    pub locked_seam_view_camera: Option<SeamViewCameraLock>,
     pub fps_string: String,
     pub export_form: Option<SeamExportForm>,
     pub export_progress: Arc<Mutex<Option<ExportProgress>>>,
+    pub export_cancel: Arc<AtomicBool>,
+    pub server: Option<Server>,
+    pub server_error: Option<String>,
+    pub scripts: ScriptHost,
+    pub show_scripts: bool,
+    pub show_area_report: bool,
+    pub area_report_name: String,
+    pub area_report_progress: Arc<Mutex<Option<ExportProgress>>>,
+    pub area_report_cancel: Arc<AtomicBool>,
+    pub mesh_export_form: Option<MeshExportForm>,
+    pub show_seam_list: bool,
+    pub seam_list_search: String,
+    pub bookmarks: Bookmarks,
+    pub bookmarks_error: Option<String>,
+    pub show_bookmarks: bool,
+    /// The index in the surface pool of the surface shown in the surface inspector.
+    pub inspected_surface: Option<usize>,
+    pub surface_visibility: SurfaceVisibility,
+    pub show_surface_visibility: bool,
+    pub camera_mode: CameraMode,
+    pub free_fly_camera: FreeFlyCamera,
+    pub birds_eye_camera: BirdsEyeCamera,
+    /// True while the world view camera is being moved by right dragging.
+    pub camera_dragging: bool,
+    /// The built in palettes followed by the ones from the config file.
+    pub palettes: Vec<Palette>,
+    pub settings: Settings,
+    pub show_log: bool,
+    /// The least severe level shown in the log window.
+    pub log_level: Level,
+    pub diagnostic_bundle_status: Option<String>,
+    /// Mario's position on recent game frames, oldest first.
+    pub mario_trail: VecDeque<Point3f>,
+    /// The global timer when Mario's position was last added to the trail.
+    pub mario_trail_timer: Option<u32>,
 }
 
 impl ConnectedView {
-    pub fn new(pid: u32, base_address: usize, globals: Globals) -> Self {
-        Self {
+    pub fn new(
+        pid: u32,
+        base_address: usize,
+        globals: Globals,
+        palettes: Vec<Palette>,
+        settings: Settings,
+    ) -> Self {
+        let (bookmarks, bookmarks_error) = match Bookmarks::load(BOOKMARKS_FILE) {
+            Ok(bookmarks) => (bookmarks, None),
+            Err(error) => {
+                // Keep a copy so that the broken file isn't lost when a bookmark is saved
+                let backup = format!("{}.bak", BOOKMARKS_FILE);
+                let _ = fs::copy(BOOKMARKS_FILE, &backup);
+                log::error!("Failed to load {}: {}", BOOKMARKS_FILE, error);
+                (
+                    Bookmarks::default(),
+                    Some(format!(
+                        "Failed to load {} (copied to {}): {}",
+                        BOOKMARKS_FILE, backup, error
+                    )),
+                )
+            }
+        };
+
+        let mut view = Self {
             process: Process::attach(pid, base_address),
             globals,
             sync_to_game: false,
             seam_processor: SeamProcessor::new(),
             hovered_seam: None,
-            seam_view: None,
+            seam_views: Vec::new(),
+            active_seam_view: 0,
+            select_seam_view_tab: false,
+            seam_views_side_by_side: false,
+            lock_seam_view_cameras: false,
+            locked_seam_view_camera: None,
             fps_string: String::new(),
             export_form: None,
             export_progress: Arc::new(Mutex::new(None)),
+            export_cancel: Arc::new(AtomicBool::new(false)),
+            server: None,
+            server_error: None,
+            scripts: ScriptHost::new(),
+            show_scripts: false,
+            show_area_report: false,
+            area_report_name: DEFAULT_REPORT_NAME.to_owned(),
+            area_report_progress: Arc::new(Mutex::new(None)),
+            area_report_cancel: Arc::new(AtomicBool::new(false)),
+            mesh_export_form: None,
+            show_seam_list: false,
+            seam_list_search: String::new(),
+            bookmarks,
+            bookmarks_error,
+            show_bookmarks: false,
+            inspected_surface: None,
+            surface_visibility: SurfaceVisibility::default(),
+            show_surface_visibility: false,
+            camera_mode: CameraMode::Follow,
+            free_fly_camera: FreeFlyCamera::default(),
+            birds_eye_camera: BirdsEyeCamera {
+                pos: [0.0, BIRDS_EYE_CAMERA_Y, 0.0],
+                span_y: DEFAULT_BIRDS_EYE_SPAN,
+            },
+            camera_dragging: false,
+            palettes,
+            settings: Settings::default(),
+            show_log: false,
+            log_level: Level::Info,
+            diagnostic_bundle_status: None,
+            mario_trail: VecDeque::new(),
+            mario_trail_timer: None,
+        };
+        view.apply_settings(settings);
+        view
+    }
+
+    /// The selected palette, or the default palette if it no longer exists.
+    pub fn palette(&self) -> &Palette {
+        self.palettes
+            .iter()
+            .find(|palette| palette.name == self.settings.palette)
+            .unwrap_or(&self.palettes[0])
+    }
+
+    /// The seam view in the selected tab.
+    pub fn seam_view(&self) -> Option<&SeamViewState> {
+        self.seam_views.get(self.active_seam_view)
+    }
+
+    /// Show a seam in the selected tab, or in a new tab if `new_tab` is set or no seam view is
+    /// open. If the seam is already open, its tab is selected instead.
+    pub fn open_seam_view(&mut self, seam: Seam, new_tab: bool) -> &mut SeamViewState {
+        let existing = self
+            .seam_views
+            .iter()
+            .position(|seam_view| seam_view.seam == seam);
+        if let (Some(index), false) = (existing, new_tab) {
+            self.active_seam_view = index;
+        } else {
+            let seam_view = SeamViewState::new(seam, self.seam_processor.filter().clone());
+            if new_tab || self.seam_views.is_empty() {
+                self.seam_views.push(seam_view);
+                self.active_seam_view = self.seam_views.len() - 1;
+            } else {
+                let old = mem::replace(&mut self.seam_views[self.active_seam_view], seam_view);
+                self.seam_processor.remove_focused_seam(old.id);
+            }
+        }
+        self.select_seam_view_tab = true;
+        &mut self.seam_views[self.active_seam_view]
+    }
+
+    pub fn close_seam_view(&mut self, index: usize) {
+        let seam_view = self.seam_views.remove(index);
+        self.seam_processor.remove_focused_seam(seam_view.id);
+        if self.active_seam_view > index || self.active_seam_view >= self.seam_views.len() {
+            self.active_seam_view = self.active_seam_view.saturating_sub(1);
         }
+        self.select_seam_view_tab = true;
     }
+
+    /// Add Mario's position to the trail once per game frame. The trail is cleared if the game
+    /// goes back in time, e.g. when a savestate is loaded.
+    pub fn update_mario_trail(&mut self, state: &GameState) {
+        let Some(mario) = state.mario else {
+            self.mario_trail.clear();
+            return;
+        };
+        if self
+            .mario_trail_timer
+            .is_some_and(|timer| state.global_timer < timer)
+        {
+            self.mario_trail.clear();
+        }
+        if self.mario_trail_timer != Some(state.global_timer) {
+            self.mario_trail_timer = Some(state.global_timer);
+            self.mario_trail.push_back(mario.pos());
+        }
+        while self.mario_trail.len() > self.settings.mario_trail_frames {
+            self.mario_trail.pop_front();
+        }
+    }
+
+    /// Replace the settings and update the view to match them.
+    pub fn apply_settings(&mut self, settings: Settings) {
+        self.sync_to_game = settings.sync_to_game;
+        // Custom filters may no longer exist if the scripts have changed
+        self.seam_processor
+            .set_filter(PointFilter::from_name(&settings.point_filter_name).unwrap_or_default());
+        self.settings = settings;
+    }
+}
+
+/// How the world view camera is positioned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CameraMode {
+    /// Mirror the in-game camera.
+    Follow,
+    FreeFly,
+    BirdsEye,
 }
 
+impl CameraMode {
+    pub fn all() -> Vec<Self> {
+        vec![Self::Follow, Self::FreeFly, Self::BirdsEye]
+    }
+}
+
+impl Display for CameraMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CameraMode::Follow => write!(f, "follow game"),
+            CameraMode::FreeFly => write!(f, "free-fly"),
+            CameraMode::BirdsEye => write!(f, "birds-eye"),
+        }
+    }
+}
+
+/// A perspective camera that moves independently of the game.
+#[derive(Debug, Clone, Default)]
+pub struct FreeFlyCamera {
+    pub pos: Point3f,
+    pub pitch: f32,
+    pub yaw: f32,
+}
+
+impl FreeFlyCamera {
+    pub fn dir(&self) -> Vector3f {
+        pitch_yaw_to_direction(self.pitch, self.yaw)
+    }
+}
+
+/// Which surfaces are hidden in the world view.
+///
+/// Surfaces hidden by clicking are identified by their vertices rather than their index, since
+/// the surface pool is rebuilt when changing areas.
+#[derive(Debug, Default)]
+pub struct SurfaceVisibility {
+    pub hidden: HashSet<[[i16; 3]; 3]>,
+    pub hide_floors: bool,
+    pub hide_ceilings: bool,
+    pub hide_walls: bool,
+    pub isolated_room: Option<i8>,
+    /// Only show surfaces that overlap the box with the given center and half width.
+    pub isolated_region: Option<([f32; 3], f32)>,
+}
+
+impl SurfaceVisibility {
+    pub fn hide(&mut self, surface: &Surface) {
+        self.hidden
+            .insert([surface.vertex1, surface.vertex2, surface.vertex3]);
+    }
+
+    pub fn is_hidden(&self, surface: &Surface) -> bool {
+        let hidden_type = if surface.normal[1] > 0.01 {
+            self.hide_floors
+        } else if surface.normal[1] < -0.01 {
+            self.hide_ceilings
+        } else {
+            self.hide_walls
+        };
+
+        let vertices = [surface.vertex1, surface.vertex2, surface.vertex3];
+        let outside_region = self.isolated_region.is_some_and(|(center, radius)| {
+            (0..3).any(|i| {
+                let min = vertices.iter().map(|v| v[i]).min().unwrap() as f32;
+                let max = vertices.iter().map(|v| v[i]).max().unwrap() as f32;
+                max < center[i] - radius || min > center[i] + radius
+            })
+        });
+
+        hidden_type
+            || outside_region
+            || self.isolated_room.is_some_and(|room| room != surface.room)
+            || self.hidden.contains(&vertices)
+    }
+
+    pub fn hidden_indices(&self, surfaces: &[Surface]) -> HashSet<usize> {
+        surfaces
+            .iter()
+            .enumerate()
+            .filter(|(_, surface)| self.is_hidden(surface))
+            .map(|(i, _)| i)
+            .collect()
+    }
+}
+
+/// The w and y at the center of a seam view, and the height of the view.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SeamViewCameraLock {
+    pub w: f64,
+    pub y: f64,
+    pub span_y: f64,
+}
+
+static NEXT_SEAM_VIEW_ID: AtomicUsize = AtomicUsize::new(0);
+
 #[derive(Debug)]
 pub struct SeamViewState {
+    /// A unique id, used to tell seam views apart when their seams are the same.
+    pub id: usize,
     pub seam: Seam,
+    pub filter: PointFilter,
     pub camera_pos: Point3<f64>,
     pub mouse_drag_start_pos: Option<Point3<f64>>,
     pub zoom: f64,
     pub initial_span_y: Option<f64>,
+    pub overlay_filename_buffer: String,
+    pub overlay: Option<SeamOverlay>,
+    pub overlay_load: Option<OverlayLoad>,
+    pub overlay_error: Option<String>,
+    /// The last point under the mouse, used by the copy buttons.
+    pub hovered_point: Option<ProjectedPoint<f32>>,
+    pub jump_w_buffer: String,
+    pub jump_y_buffer: String,
+    /// The zoom to jump to. If empty, "Go" keeps the current zoom.
+    pub jump_zoom_buffer: String,
+    pub jump_error: Option<String>,
+    pub point_search: Option<PointSearch>,
+    pub point_search_message: Option<String>,
+    /// A point to center on once the camera has been initialized.
+    pub pending_jump: Option<ProjectedPoint<f32>>,
+    pub bookmark_form: Option<BookmarkForm>,
+    pub histogram: Option<GapHistogram>,
 }
 
 impl SeamViewState {
-    pub fn new(seam: Seam) -> Self {
+    pub fn new(seam: Seam, filter: PointFilter) -> Self {
         let camera_pos = seam.endpoint1() + (seam.endpoint2() - seam.endpoint1()) / 2.0;
         Self {
+            id: NEXT_SEAM_VIEW_ID.fetch_add(1, Ordering::Relaxed),
             seam,
+            filter,
             camera_pos: point_f32_to_f64(camera_pos),
             mouse_drag_start_pos: None,
             zoom: 0.0,
             initial_span_y: None,
+            overlay_filename_buffer: String::with_capacity(32),
+            overlay: None,
+            overlay_load: None,
+            overlay_error: None,
+            hovered_point: None,
+            jump_w_buffer: String::with_capacity(32),
+            jump_y_buffer: String::with_capacity(32),
+            jump_zoom_buffer: String::with_capacity(16),
+            jump_error: None,
+            point_search: None,
+            point_search_message: None,
+            pending_jump: None,
+            bookmark_form: None,
+            histogram: None,
+        }
+    }
+
+    /// Return the current camera in w/y coordinates, or None if the view hasn't been shown yet.
+    pub fn camera_lock(&self) -> Option<SeamViewCameraLock> {
+        let w = match self.seam.edge1.projection_axis {
+            ProjectionAxis::X => self.camera_pos.z,
+            ProjectionAxis::Z => self.camera_pos.x,
+        };
+        Some(SeamViewCameraLock {
+            w,
+            y: self.camera_pos.y,
+            span_y: self.initial_span_y? / 2.0f64.powf(self.zoom),
+        })
+    }
+
+    pub fn set_camera_lock(&mut self, lock: SeamViewCameraLock) {
+        let Some(initial_span_y) = self.initial_span_y else {
+            return;
+        };
+        match self.seam.edge1.projection_axis {
+            ProjectionAxis::X => self.camera_pos.z = lock.w,
+            ProjectionAxis::Z => self.camera_pos.x = lock.w,
+        }
+        self.camera_pos.y = lock.y;
+        self.zoom = (initial_span_y / lock.span_y).log2();
+    }
+}
+
+#[derive(Debug)]
+pub struct BookmarkForm {
+    pub name: String,
+    pub comment: String,
+    pub w_buffer: String,
+    pub y_buffer: String,
+    pub error: Option<String>,
+}
+
+impl BookmarkForm {
+    pub fn new(point: ProjectedPoint<f32>) -> Self {
+        Self {
+            name: String::with_capacity(32),
+            comment: String::with_capacity(64),
+            w_buffer: format!("{}", point.w),
+            y_buffer: format!("{}", point.y),
+            error: None,
         }
     }
 }
 
+pub type FoundPoint = (ProjectedPoint<f32>, PointStatus);
+
+/// A background search for the next or previous gap or overlap in the seam view.
+#[derive(Debug)]
+pub struct PointSearch {
+    pub forward: bool,
+    /// Set when the search finishes, to the point found (if any).
+    pub result: Arc<Mutex<Option<Option<FoundPoint>>>>,
+    pub cancel: Arc<AtomicBool>,
+}
+
+impl Drop for PointSearch {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+/// The number of gaps and overlaps in a range, as `(num_gaps, num_overlaps)`.
+pub type PointCounts = (usize, usize);
+
+/// A background count of the gaps and overlaps along a whole seam, shown as a histogram in the
+/// seam view.
+#[derive(Debug)]
+pub struct GapHistogram {
+    pub filter: PointFilter,
+    pub buckets: Vec<RangeF32>,
+    /// The counts for each bucket, or None if it hasn't been counted yet.
+    pub counts: Arc<Mutex<Vec<Option<PointCounts>>>>,
+    pub cancel: Arc<AtomicBool>,
+}
+
+impl Drop for GapHistogram {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+/// The points read from an overlay file, and whether they were truncated.
+pub type OverlayPoints = (Vec<ExportedPoint>, bool);
+
+/// A background load of an overlay file.
+#[derive(Debug)]
+pub struct OverlayLoad {
+    pub filename: String,
+    pub progress: Arc<Mutex<Option<ExportProgress>>>,
+    /// Set when the load finishes.
+    pub result: Arc<Mutex<Option<io::Result<OverlayPoints>>>>,
+    pub cancel: Arc<AtomicBool>,
+}
+
+impl Drop for OverlayLoad {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Points from a previous export, shown on top of the seam view for comparison.
+#[derive(Debug)]
+pub struct SeamOverlay {
+    pub filename: String,
+    pub points: Vec<ExportedPoint>,
+    /// True if the file had more than `MAX_OVERLAY_POINTS` points.
+    pub truncated: bool,
+}
+
 #[derive(Debug)]
 pub struct SeamExportForm {
     pub seam: Seam,
@@ -104,17 +578,25 @@ pub struct SeamExportForm {
     pub filename_buffer: String,
     pub point_filter: PointFilter,
     pub status_filter: PointStatusFilter,
+    pub format: ExportFormat,
+    pub compression: Compression,
     pub include_small_w: bool,
     pub min_w: Option<f32>,
     pub max_w: Option<f32>,
     pub min_w_buffer: String,
     pub max_w_buffer: String,
+    pub available_space: Option<u64>,
+    pub checkpoint: Option<ExportCheckpoint>,
+    pub resume_error: Option<String>,
+    /// The export started from this form, until it finishes.
+    pub export_thread: Option<JoinHandle<()>>,
+    estimate: Option<(ExportFormat, ExportOptions, ExportEstimate)>,
 }
 
 impl SeamExportForm {
-    pub fn new(seam: Seam, filter: PointFilter) -> Self {
+    pub fn new(seam: Seam, filter: PointFilter, defaults: &ExportDefaults) -> Self {
         let w_range = seam.w_range();
-        let mut filename_buffer = "seam.csv".to_string();
+        let mut filename_buffer = defaults.filename.clone();
         filename_buffer.reserve(32);
         let mut min_w_buffer = format!("{}", w_range.start);
         min_w_buffer.reserve(32);
@@ -122,22 +604,73 @@ impl SeamExportForm {
         max_w_buffer.reserve(32);
 
         Self {
+            available_space: available_space(&filename_buffer),
+            checkpoint: load_checkpoint(&filename_buffer),
+            resume_error: None,
+            export_thread: None,
+            estimate: None,
             seam,
             filename: Some(filename_buffer.to_string()),
             filename_buffer,
             point_filter: filter,
-            status_filter: PointStatusFilter::GapsAndOverlaps,
-            include_small_w: false,
+            status_filter: defaults.status_filter,
+            format: defaults.format,
+            compression: defaults.compression,
+            include_small_w: defaults.include_small_w,
             min_w: Some(w_range.start),
             max_w: Some(prev_f32(w_range.end)),
             min_w_buffer,
             max_w_buffer,
         }
     }
+
+    pub fn options(&self) -> Option<ExportOptions> {
+        Some(ExportOptions {
+            point_filter: self.point_filter.clone(),
+            status_filter: self.status_filter,
+            include_small_w: self.include_small_w,
+            w_range: RangeF32::inclusive(self.min_w?, self.max_w?),
+        })
+    }
+
+    /// Estimate the size of the export, reusing the previous estimate if nothing has changed.
+    pub fn estimate(&mut self) -> Option<ExportEstimate> {
+        let options = self.options()?;
+        match &self.estimate {
+            Some((format, prev_options, estimate))
+                if *format == self.format && *prev_options == options =>
+            {
+                Some(*estimate)
+            }
+            _ => {
+                let estimate = estimate_export_size(&self.seam, self.format, &options);
+                self.estimate = Some((self.format, options, estimate));
+                Some(estimate)
+            }
+        }
+    }
 }
 
 #[derive(Debug)]
-pub struct ExportProgress {
-    pub complete: usize,
-    pub total: usize,
+pub struct MeshExportForm {
+    pub name: String,
+    pub format: MeshFormat,
+    /// Set when the export button is pressed, and handled when the game view scene is built.
+    pub requested: bool,
+    pub status: Option<String>,
+}
+
+impl MeshExportForm {
+    pub fn new() -> Self {
+        Self {
+            name: DEFAULT_MESH_NAME.to_owned(),
+            format: MeshFormat::Ply,
+            requested: false,
+            status: None,
+        }
+    }
+
+    pub fn filename(&self) -> String {
+        format!("{}.{}", self.name.trim(), self.format.extension())
+    }
 }
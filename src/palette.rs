@@ -0,0 +1,152 @@
+use crate::seam::{PointStatus, RangeStatus};
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_PALETTE: &str = "default";
+
+/// The colors used for gaps, overlaps and unchecked ranges in the world view and seam view, and
+/// for the markers drawn on top of them.
+///
+/// Colors are `[r, g, b]` with components from 0 to 1. Extra palettes can be added to the
+/// `palettes` list in config.json. Missing colors are taken from the default palette.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Palette {
+    pub name: String,
+    pub gap: [f32; 3],
+    pub overlap: [f32; 3],
+    pub gap_and_overlap: [f32; 3],
+    pub neither: [f32; 3],
+    pub unchecked: [f32; 3],
+    pub skipped: [f32; 3],
+    pub bookmark: [f32; 3],
+    pub mario: [f32; 3],
+    pub mario_trail: [f32; 3],
+    /// Points loaded from a previous export.
+    pub exported_point: [f32; 3],
+    /// Exported points that don't match the live computation.
+    pub exported_point_differs: [f32; 3],
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self {
+            name: DEFAULT_PALETTE.to_owned(),
+            gap: [0.0, 1.0, 0.0],
+            overlap: [0.1, 0.1, 1.0],
+            gap_and_overlap: [0.0, 1.0, 1.0],
+            neither: [1.0, 1.0, 1.0],
+            unchecked: [0.1, 0.1, 0.1],
+            skipped: [1.0, 0.0, 0.0],
+            bookmark: [1.0, 0.85, 0.0],
+            mario: [1.0, 0.4, 0.7],
+            mario_trail: [1.0, 0.7, 0.85],
+            exported_point: [1.0, 0.55, 0.0],
+            exported_point_differs: [1.0, 0.0, 1.0],
+        }
+    }
+}
+
+impl Palette {
+    pub fn builtin() -> Vec<Self> {
+        vec![
+            Self::default(),
+            // Okabe-Ito colors, which are distinguishable with the common kinds of color blindness
+            Self {
+                name: "color blind safe".to_owned(),
+                gap: [0.9, 0.62, 0.0],
+                overlap: [0.34, 0.71, 0.91],
+                gap_and_overlap: [0.94, 0.89, 0.26],
+                neither: [1.0, 1.0, 1.0],
+                unchecked: [0.1, 0.1, 0.1],
+                skipped: [0.8, 0.47, 0.65],
+                bookmark: [0.0, 0.45, 0.7],
+                mario: [0.75, 0.75, 0.75],
+                mario_trail: [0.45, 0.45, 0.45],
+                exported_point: [0.0, 0.62, 0.45],
+                exported_point_differs: [1.0, 1.0, 1.0],
+            },
+            Self {
+                name: "high contrast".to_owned(),
+                gap: [1.0, 1.0, 0.0],
+                overlap: [1.0, 0.0, 1.0],
+                gap_and_overlap: [1.0, 0.5, 0.0],
+                neither: [1.0, 1.0, 1.0],
+                unchecked: [0.25, 0.25, 0.25],
+                skipped: [0.0, 0.6, 1.0],
+                bookmark: [0.0, 1.0, 0.0],
+                mario: [1.0, 0.0, 0.0],
+                mario_trail: [0.6, 0.0, 0.0],
+                exported_point: [0.0, 1.0, 1.0],
+                exported_point_differs: [1.0, 1.0, 1.0],
+            },
+        ]
+    }
+
+    pub fn segment_color(&self, status: RangeStatus) -> [f32; 4] {
+        let color = match status {
+            RangeStatus::Checked {
+                has_gap: false,
+                has_overlap: false,
+            } => self.neither,
+            RangeStatus::Checked {
+                has_gap: true,
+                has_overlap: false,
+            } => self.gap,
+            RangeStatus::Checked {
+                has_gap: false,
+                has_overlap: true,
+            } => self.overlap,
+            RangeStatus::Checked {
+                has_gap: true,
+                has_overlap: true,
+            } => self.gap_and_overlap,
+            RangeStatus::Unchecked => self.unchecked,
+            RangeStatus::Skipped => self.skipped,
+        };
+        opaque(color)
+    }
+
+    pub fn point_color(&self, status: PointStatus) -> [f32; 4] {
+        opaque(match status {
+            PointStatus::Gap => self.gap,
+            PointStatus::Overlap => self.overlap,
+            PointStatus::None => self.neither,
+        })
+    }
+
+    pub fn bookmark_color(&self) -> [f32; 4] {
+        opaque(self.bookmark)
+    }
+
+    pub fn mario_color(&self) -> [f32; 4] {
+        opaque(self.mario)
+    }
+
+    pub fn mario_trail_color(&self) -> [f32; 4] {
+        opaque(self.mario_trail)
+    }
+
+    pub fn exported_point_color(&self, differs: bool) -> [f32; 4] {
+        opaque(if differs {
+            self.exported_point_differs
+        } else {
+            self.exported_point
+        })
+    }
+
+    /// The label and color of each entry in the legend.
+    pub fn legend(&self) -> Vec<(&'static str, [f32; 4])> {
+        vec![
+            ("gap", opaque(self.gap)),
+            ("overlap", opaque(self.overlap)),
+            ("gap and overlap", opaque(self.gap_and_overlap)),
+            ("neither", opaque(self.neither)),
+            ("not yet checked", opaque(self.unchecked)),
+            ("skipped", opaque(self.skipped)),
+        ]
+    }
+}
+
+fn opaque([r, g, b]: [f32; 3]) -> [f32; 4] {
+    [r, g, b, 1.0]
+}
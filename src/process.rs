@@ -10,7 +10,7 @@ pub struct Process {
 impl Process {
     pub fn attach(pid: u32, base_address: usize) -> Self {
         Self {
-            handle: pid.try_into().unwrap(),
+            handle: (pid as read_process_memory::Pid).try_into().unwrap(),
             base_address,
         }
     }
@@ -0,0 +1,454 @@
+use crate::{
+    export::{ExportProgress, SeamRun, export_w_ranges},
+    float_range::{RangeF32, prev_f32},
+    game_state::Surface,
+    seam::{PointFilter, PointStatus, RangeStatus, Seam},
+    seam_processor::SeamProgress,
+    util::split_into_chunks,
+};
+use rayon::prelude::*;
+use serde::Serialize;
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+pub const DEFAULT_REPORT_NAME: &str = "area_report";
+
+/// The number of gaps and overlaps on a seam when using a given point filter.
+#[derive(Debug, Clone, Serialize)]
+pub struct FilterCounts {
+    pub filter: String,
+    pub gaps: u64,
+    pub overlaps: u64,
+    /// The longest run of consecutive floats that are all gaps.
+    pub longest_gap: Option<SeamRun>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SeamReport {
+    pub seam: Seam,
+    pub w_range: RangeF32,
+    /// The segments computed by the seam processor using `AreaSummary::segment_filter`.
+    pub segments: Vec<(RangeF32, RangeStatus)>,
+    pub counts: Vec<FilterCounts>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FilterSummary {
+    pub filter: String,
+    pub seams_with_gaps: usize,
+    pub seams_with_overlaps: usize,
+    pub gaps: u64,
+    pub overlaps: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AreaSummary {
+    /// Seconds since the Unix epoch.
+    pub generated_at: u64,
+    pub surfaces: usize,
+    pub walls: usize,
+    pub seams: usize,
+    pub segment_filter: String,
+    pub filters: Vec<FilterSummary>,
+}
+
+/// A summary of every active seam in the current area.
+///
+/// Counts exclude the range [-1, 1], like the rest of the program.
+#[derive(Debug, Clone, Serialize)]
+pub struct AreaReport {
+    pub summary: AreaSummary,
+    pub seams: Vec<SeamReport>,
+}
+
+/// Check every float on every seam with each of the built-in point filters, and with
+/// `segment_filter` if it is a custom filter. This can take a long time, so
+/// `set_progress` is called periodically, and the report stops with an `Interrupted` error if
+/// `cancel` is set.
+pub fn build_area_report(
+    surfaces: &[Surface],
+    seams: &[(Seam, SeamProgress)],
+    segment_filter: PointFilter,
+    set_progress: impl Fn(Option<ExportProgress>) + Sync,
+    cancel: &AtomicBool,
+) -> io::Result<AreaReport> {
+    // Custom filters run a script for every float, so only the selected one is included
+    let filters: Vec<PointFilter> = PointFilter::all()
+        .into_iter()
+        .filter(|filter| !matches!(filter, PointFilter::Custom(_)) || *filter == segment_filter)
+        .collect();
+    let total = seams
+        .iter()
+        .flat_map(|(seam, _)| export_w_ranges(seam.w_range(), false))
+        .map(|range| range.count())
+        .sum();
+    let complete = AtomicUsize::new(0);
+    let add_progress = |count: usize| {
+        let complete = complete.fetch_add(count, Ordering::Relaxed) + count;
+        set_progress(Some(ExportProgress { complete, total }));
+    };
+
+    let seam_reports = seams
+        .par_iter()
+        .map(|(seam, progress)| {
+            let chunks = split_into_chunks(seam.w_range());
+            let chunk_counts = chunks
+                .par_iter()
+                .map(|chunk| {
+                    if cancel.load(Ordering::Relaxed) {
+                        return Err(io::Error::new(
+                            io::ErrorKind::Interrupted,
+                            "report cancelled",
+                        ));
+                    }
+                    let counts: Vec<ChunkCounts> = filters
+                        .iter()
+                        .map(|filter| ChunkCounts::count(seam, *chunk, filter))
+                        .collect();
+                    add_progress(chunk.count());
+                    Ok(counts)
+                })
+                .collect::<io::Result<Vec<_>>>()?;
+
+            let counts = merge_chunk_counts(&filters, &chunks, chunk_counts);
+
+            Ok(SeamReport {
+                seam: seam.clone(),
+                w_range: seam.w_range(),
+                segments: progress.segments().collect(),
+                counts,
+            })
+        })
+        .collect::<io::Result<Vec<_>>>()?;
+
+    let summary = AreaSummary {
+        generated_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or(0),
+        surfaces: surfaces.len(),
+        walls: surfaces
+            .iter()
+            .filter(|surface| surface.normal[1].abs() <= 0.01)
+            .count(),
+        seams: seams.len(),
+        segment_filter: segment_filter.to_string(),
+        filters: filters
+            .iter()
+            .enumerate()
+            .map(|(i, filter)| {
+                let counts = seam_reports.iter().map(|report| &report.counts[i]);
+                FilterSummary {
+                    filter: filter.to_string(),
+                    seams_with_gaps: counts.clone().filter(|counts| counts.gaps > 0).count(),
+                    seams_with_overlaps: counts
+                        .clone()
+                        .filter(|counts| counts.overlaps > 0)
+                        .count(),
+                    gaps: counts.clone().map(|counts| counts.gaps).sum(),
+                    overlaps: counts.map(|counts| counts.overlaps).sum(),
+                }
+            })
+            .collect(),
+    };
+
+    set_progress(None);
+    Ok(AreaReport {
+        summary,
+        seams: seam_reports,
+    })
+}
+
+/// Combine the counts for consecutive chunks of a seam, joining gap runs that cross chunk
+/// boundaries.
+fn merge_chunk_counts(
+    filters: &[PointFilter],
+    chunks: &[RangeF32],
+    chunk_counts: Vec<Vec<ChunkCounts>>,
+) -> Vec<FilterCounts> {
+    let mut counts: Vec<FilterCounts> = filters
+        .iter()
+        .map(|filter| FilterCounts {
+            filter: filter.to_string(),
+            gaps: 0,
+            overlaps: 0,
+            longest_gap: None,
+        })
+        .collect();
+    // The gap run that continues at the end of the previous chunk
+    let mut current_gaps: Vec<Option<SeamRun>> = vec![None; filters.len()];
+    let mut prev_end = None;
+
+    for (chunk, chunk_counts) in chunks.iter().zip(chunk_counts) {
+        for ((counts, current_gap), chunk_counts) in
+            counts.iter_mut().zip(&mut current_gaps).zip(chunk_counts)
+        {
+            // Runs don't continue across the range [-1, 1]
+            if prev_end != Some(chunk.start)
+                && let Some(run) = current_gap.take()
+            {
+                record_gap(counts, run);
+            }
+            counts.gaps += chunk_counts.gaps;
+            counts.overlaps += chunk_counts.overlaps;
+
+            if let Some(leading) = chunk_counts.leading_gap {
+                match current_gap {
+                    Some(run) => extend_run(run, &leading),
+                    None => *current_gap = Some(leading),
+                }
+            }
+            if !chunk_counts.all_gaps {
+                if let Some(run) = current_gap.take() {
+                    record_gap(counts, run);
+                }
+                if let Some(run) = chunk_counts.longest_gap {
+                    record_gap(counts, run);
+                }
+                *current_gap = chunk_counts.trailing_gap;
+            }
+        }
+        prev_end = Some(chunk.end);
+    }
+    for (counts, current_gap) in counts.iter_mut().zip(current_gaps) {
+        if let Some(run) = current_gap {
+            record_gap(counts, run);
+        }
+    }
+    counts
+}
+
+/// The gaps and overlaps in a chunk of a seam for one filter. Chunks are counted in parallel, so
+/// the gap runs at either end are kept separately in case they continue into the next chunk.
+struct ChunkCounts {
+    gaps: u64,
+    overlaps: u64,
+    /// The gap run starting at the first float of the chunk.
+    leading_gap: Option<SeamRun>,
+    /// True if every float in the chunk is a gap, in which case `leading_gap` covers the chunk.
+    all_gaps: bool,
+    /// The longest gap run that touches neither end of the chunk.
+    longest_gap: Option<SeamRun>,
+    /// The gap run ending at the last float of the chunk, if it isn't `leading_gap`.
+    trailing_gap: Option<SeamRun>,
+}
+
+impl ChunkCounts {
+    fn count(seam: &Seam, chunk: RangeF32, filter: &PointFilter) -> Self {
+        let mut gaps = 0;
+        let mut overlaps = 0;
+        let mut leading_gap = None;
+        let mut longest_gap: Option<SeamRun> = None;
+        let mut current_gap: Option<SeamRun> = None;
+        let mut at_start = true;
+
+        for w in chunk.iter() {
+            let (y, status) = seam.check_point(w, filter);
+            match status {
+                PointStatus::Gap => gaps += 1,
+                PointStatus::Overlap => overlaps += 1,
+                PointStatus::None => {}
+            }
+
+            if status == PointStatus::Gap {
+                match &mut current_gap {
+                    Some(run) => run.push(w, y),
+                    None => current_gap = Some(SeamRun::new(w, y, status)),
+                }
+            } else {
+                if let Some(run) = current_gap.take() {
+                    if at_start {
+                        leading_gap = Some(run);
+                    } else if longest_gap.is_none_or(|longest| run.count > longest.count) {
+                        longest_gap = Some(run);
+                    }
+                }
+                at_start = false;
+            }
+        }
+
+        let all_gaps = at_start;
+        if all_gaps {
+            leading_gap = current_gap.take();
+        }
+        Self {
+            gaps,
+            overlaps,
+            leading_gap,
+            all_gaps,
+            longest_gap,
+            trailing_gap: current_gap,
+        }
+    }
+}
+
+/// Append a run that starts right after `run` ends.
+fn extend_run(run: &mut SeamRun, next: &SeamRun) {
+    run.end_w = next.end_w;
+    run.count += next.count;
+    run.y_min = run.y_min.min(next.y_min);
+    run.y_max = run.y_max.max(next.y_max);
+}
+
+fn record_gap(counts: &mut FilterCounts, run: SeamRun) {
+    if counts
+        .longest_gap
+        .is_none_or(|longest| run.count > longest.count)
+    {
+        counts.longest_gap = Some(run);
+    }
+}
+
+/// Write the report to `<name>.json` and `<name>.csv`.
+pub fn save_area_report(name: &str, report: &AreaReport) -> io::Result<()> {
+    fs::write(
+        format!("{}.json", name),
+        serde_json::to_string_pretty(report)?,
+    )?;
+
+    let mut writer = BufWriter::new(File::create(format!("{}.csv", name))?);
+    let summary = &report.summary;
+    writeln!(
+        writer,
+        "# surfaces: {}, walls: {}, seams: {}, generated at: {}",
+        summary.surfaces, summary.walls, summary.seams, summary.generated_at,
+    )?;
+    for filter in &summary.filters {
+        writeln!(
+            writer,
+            "# filter {}: {} gaps on {} seams, {} overlaps on {} seams",
+            filter.filter,
+            filter.gaps,
+            filter.seams_with_gaps,
+            filter.overlaps,
+            filter.seams_with_overlaps,
+        )?;
+    }
+
+    writeln!(
+        writer,
+        "seam,x1,y1,z1,x2,y2,z2,w min,w max,filter,gaps,overlaps,longest gap start,longest gap end,longest gap count"
+    )?;
+    for (i, seam_report) in report.seams.iter().enumerate() {
+        let ([x1, y1, z1], [x2, y2, z2]) = seam_report.seam.endpoints;
+        for counts in &seam_report.counts {
+            let (gap_start, gap_end, gap_count) = match counts.longest_gap {
+                Some(run) => (
+                    run.start_w.to_string(),
+                    run.end_w.to_string(),
+                    run.count.to_string(),
+                ),
+                None => (String::new(), String::new(), "0".to_owned()),
+            };
+            writeln!(
+                writer,
+                "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+                i,
+                x1,
+                y1,
+                z1,
+                x2,
+                y2,
+                z2,
+                seam_report.w_range.start,
+                prev_f32(seam_report.w_range.end),
+                counts.filter,
+                counts.gaps,
+                counts.overlaps,
+                gap_start,
+                gap_end,
+                gap_count,
+            )?;
+        }
+    }
+
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunked_counts_match_sequential_counts() {
+        // Walls facing opposite directions, so that there are runs of gaps
+        let seam = Seam::between(
+            ([0, 0, 0], [100, 37, 0]),
+            [0.0, 0.0, 1.0],
+            ([100, 37, 0], [0, 0, 0]),
+            [0.0, 0.0, -1.0],
+        )
+        .unwrap();
+        let filters = [PointFilter::None, PointFilter::QuarterIntY];
+        let w_range = RangeF32::inclusive_exclusive(50.0, 50.002);
+
+        let mut expected = merge_chunk_counts(&filters, &[], Vec::new());
+        let mut current_gaps: Vec<Option<SeamRun>> = vec![None; filters.len()];
+        for w in w_range.iter() {
+            for ((filter, counts), current_gap) in
+                filters.iter().zip(&mut expected).zip(&mut current_gaps)
+            {
+                let (y, status) = seam.check_point(w, filter);
+                match status {
+                    PointStatus::Gap => counts.gaps += 1,
+                    PointStatus::Overlap => counts.overlaps += 1,
+                    PointStatus::None => {}
+                }
+                if status == PointStatus::Gap {
+                    match current_gap {
+                        Some(run) => run.push(w, y),
+                        None => *current_gap = Some(SeamRun::new(w, y, status)),
+                    }
+                } else if let Some(run) = current_gap.take() {
+                    record_gap(counts, run);
+                }
+            }
+        }
+        for (counts, current_gap) in expected.iter_mut().zip(current_gaps) {
+            if let Some(run) = current_gap {
+                record_gap(counts, run);
+            }
+        }
+        assert!(expected[0].longest_gap.is_some_and(|run| run.count > 1));
+
+        for chunk_size in [1, 2, 7, 100, 10_000] {
+            let mut chunks = Vec::new();
+            let mut start = w_range.start;
+            while start < w_range.end {
+                let end = RangeF32::inclusive_exclusive(start, w_range.end)
+                    .nth(chunk_size)
+                    .unwrap_or(w_range.end);
+                chunks.push(RangeF32::inclusive_exclusive(start, end));
+                start = end;
+            }
+            let chunk_counts = chunks
+                .iter()
+                .map(|chunk| {
+                    filters
+                        .iter()
+                        .map(|filter| ChunkCounts::count(&seam, *chunk, filter))
+                        .collect()
+                })
+                .collect();
+
+            let counts = merge_chunk_counts(&filters, &chunks, chunk_counts);
+            for (counts, expected) in counts.iter().zip(&expected) {
+                assert_eq!(counts.gaps, expected.gaps, "chunk size {}", chunk_size);
+                assert_eq!(
+                    counts.overlaps, expected.overlaps,
+                    "chunk size {}",
+                    chunk_size
+                );
+                assert_eq!(
+                    counts.longest_gap, expected.longest_gap,
+                    "chunk size {}",
+                    chunk_size
+                );
+            }
+        }
+    }
+}
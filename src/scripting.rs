@@ -0,0 +1,479 @@
+use crate::{
+    edge::ProjectedPoint,
+    export::{ExportOptions, save_seam_to_csv},
+    game_state::{GameState, Surface},
+    graphics::SeamInfo,
+    seam::{self, CustomFilterFn, PointFilter, RangeStatus, Seam},
+    seam_processor::SeamProcessor,
+};
+use rhai::{
+    AST, Array, CallFnOptions, Dynamic, Engine, EvalAltResult, FLOAT, Map, Scope,
+    serde::{from_dynamic, to_dynamic},
+};
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::BufWriter,
+    path::Path,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
+};
+
+pub const SCRIPT_DIR: &str = "scripts";
+
+const MAX_JOB_OUTPUT_LINES: usize = 200;
+
+/// The operation limit for `point_filter` and `seam_color`, which are called for every point or
+/// segment. A script that exceeds it (e.g. an infinite loop) fails instead of hanging the caller.
+/// `main` has no limit, but can be stopped from the scripts window.
+const MAX_CALLBACK_OPERATIONS: u64 = 100_000;
+
+/// The game data that scripts can access, copied from the UI thread once per frame.
+#[derive(Debug, Default)]
+struct ScriptContext {
+    seams: Vec<Seam>,
+    surfaces: Vec<Surface>,
+    filter: PointFilter,
+}
+
+#[derive(Debug, Default)]
+pub struct ScriptJob {
+    pub running: Option<String>,
+    pub output: Vec<String>,
+}
+
+impl ScriptJob {
+    fn print(&mut self, line: String) {
+        log::info!("[script] {}", line);
+        self.output.push(line);
+        if self.output.len() > MAX_JOB_OUTPUT_LINES {
+            self.output.remove(0);
+        }
+    }
+}
+
+/// A script loaded from `SCRIPT_DIR`.
+///
+/// A script can define any of the following functions:
+/// - `point_filter(w, y)`: adds a point filter with the same name as the script
+/// - `seam_color(seam, segment)`: returns `[r, g, b, a]` to override a segment's color in the
+///   game view, or `()` to use the default color
+/// - `main()`: a batch job that can be run from the scripts window
+#[derive(Debug)]
+pub struct Script {
+    pub name: String,
+    ast: Arc<AST>,
+    pub has_point_filter: bool,
+    pub has_seam_color: bool,
+    pub has_main: bool,
+}
+
+/// The start w, end w and status of a segment.
+type SegmentKey = (u32, u32, RangeStatus);
+
+/// The segments that were colored and their colors.
+type SeamColors = (Vec<SegmentKey>, Vec<Option<[f32; 4]>>);
+
+/// Colors returned by `seam_color`, reused until the coloring script or a seam's segments change.
+#[derive(Debug, Default)]
+struct SeamColorCache {
+    script: Option<usize>,
+    seams: HashMap<Seam, SeamColors>,
+}
+
+#[derive(Debug)]
+pub struct ScriptHost {
+    engine: Arc<Engine>,
+    callback_engine: Arc<Engine>,
+    context: Arc<Mutex<ScriptContext>>,
+    pub scripts: Vec<Script>,
+    pub errors: Vec<String>,
+    /// Errors from point filters, which run on the seam processor's threads.
+    filter_errors: Arc<Mutex<Vec<String>>>,
+    pub coloring_script: Option<usize>,
+    color_cache: SeamColorCache,
+    pub job: Arc<Mutex<ScriptJob>>,
+    /// Set to stop the running job.
+    job_cancel: Arc<AtomicBool>,
+}
+
+impl ScriptHost {
+    pub fn new() -> Self {
+        let context = Arc::new(Mutex::new(ScriptContext::default()));
+        let job = Arc::new(Mutex::new(ScriptJob::default()));
+        let job_cancel = Arc::new(AtomicBool::new(false));
+
+        let mut engine = create_engine(&context, &job, &job_cancel);
+        let job_cancel2 = Arc::clone(&job_cancel);
+        engine.on_progress(move |_| {
+            job_cancel2
+                .load(Ordering::Relaxed)
+                .then(|| "stopped".into())
+        });
+
+        let mut callback_engine = create_engine(&context, &job, &job_cancel);
+        callback_engine.set_max_operations(MAX_CALLBACK_OPERATIONS);
+
+        let mut host = Self {
+            engine: Arc::new(engine),
+            callback_engine: Arc::new(callback_engine),
+            context,
+            scripts: Vec::new(),
+            errors: Vec::new(),
+            filter_errors: Arc::new(Mutex::new(Vec::new())),
+            coloring_script: None,
+            color_cache: SeamColorCache::default(),
+            job,
+            job_cancel,
+        };
+        host.reload();
+        host
+    }
+
+    /// Reload all scripts from disk.
+    ///
+    /// Custom point filters are replaced. Existing `PointFilter::Custom` values keep working, but
+    /// are no longer listed by `PointFilter::all`, so the caller should reset any that are selected.
+    pub fn reload(&mut self) {
+        self.scripts.clear();
+        self.errors.clear();
+        self.filter_errors.lock().unwrap().clear();
+        self.coloring_script = None;
+        self.color_cache = SeamColorCache::default();
+
+        let mut paths: Vec<_> = match fs::read_dir(SCRIPT_DIR) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().is_some_and(|ext| ext == "rhai"))
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+        paths.sort();
+
+        for path in paths {
+            match self.load_script(&path) {
+                Ok(script) => self.scripts.push(script),
+                Err(error) => {
+                    let error = format!("{}: {}", path.display(), error);
+                    log::error!("Failed to load script {}", error);
+                    self.errors.push(error);
+                }
+            }
+        }
+
+        let filters = self
+            .scripts
+            .iter()
+            .filter(|script| script.has_point_filter)
+            .map(|script| {
+                let engine = Arc::clone(&self.callback_engine);
+                let ast = Arc::clone(&script.ast);
+                let name = script.name.clone();
+                let errors = Arc::clone(&self.filter_errors);
+                // Only report the first error, since the filter is called for every point
+                let reported = AtomicBool::new(false);
+                let filter: Arc<CustomFilterFn> = Arc::new(move |point: ProjectedPoint<f32>| {
+                    match call_fn::<bool>(&engine, &ast, "point_filter", (point.w, point.y)) {
+                        Ok(matches) => matches,
+                        Err(error) => {
+                            if !reported.swap(true, Ordering::Relaxed) {
+                                let error = format!("{}: point_filter: {}", name, error);
+                                log::error!("Script error in {}", error);
+                                errors.lock().unwrap().push(error);
+                            }
+                            false
+                        }
+                    }
+                });
+                (script.name.clone(), filter)
+            })
+            .collect();
+        seam::set_custom_filters(filters);
+    }
+
+    fn load_script(&self, path: &Path) -> Result<Script, Box<EvalAltResult>> {
+        let ast = self.engine.compile_file(path.into())?;
+        let has_fn = |name: &str, num_params: usize| {
+            ast.iter_functions()
+                .any(|f| f.name == name && f.params.len() == num_params)
+        };
+
+        Ok(Script {
+            name: path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            has_point_filter: has_fn("point_filter", 2),
+            has_seam_color: has_fn("seam_color", 2),
+            has_main: has_fn("main", 0),
+            ast: Arc::new(ast),
+        })
+    }
+
+    pub fn update(&mut self, state: &GameState, seam_processor: &SeamProcessor) {
+        self.errors.append(&mut self.filter_errors.lock().unwrap());
+
+        if self.scripts.is_empty() {
+            return;
+        }
+
+        *self.context.lock().unwrap() = ScriptContext {
+            seams: seam_processor.active_seams().to_vec(),
+            surfaces: state.surfaces.clone(),
+            filter: seam_processor.filter().clone(),
+        };
+    }
+
+    /// Set `custom_color` on each segment using the selected coloring script.
+    pub fn apply_seam_colors(&mut self, seams: &mut [SeamInfo]) {
+        let Some(script_index) = self.coloring_script else {
+            return;
+        };
+        let ast = Arc::clone(&self.scripts[script_index].ast);
+
+        if self.color_cache.script != Some(script_index) {
+            self.color_cache = SeamColorCache {
+                script: Some(script_index),
+                ..Default::default()
+            };
+        }
+        let cache = &mut self.color_cache.seams;
+        cache.retain(|seam, _| seams.iter().any(|seam_info| &seam_info.seam == seam));
+
+        let result = seams.iter_mut().try_for_each(|seam_info| {
+            let keys: Vec<SegmentKey> = seam_info
+                .segments
+                .iter()
+                .map(|segment| {
+                    (
+                        segment.proj_endpoint1.w.to_bits(),
+                        segment.proj_endpoint2.w.to_bits(),
+                        segment.status,
+                    )
+                })
+                .collect();
+
+            let colors = match cache.get(&seam_info.seam) {
+                Some((cached_keys, colors)) if *cached_keys == keys => colors.clone(),
+                _ => {
+                    let seam = to_dynamic(&seam_info.seam)?;
+                    let colors = seam_info
+                        .segments
+                        .iter()
+                        .map(|segment| {
+                            let mut segment_map = Map::new();
+                            segment_map
+                                .insert("start".into(), Dynamic::from(segment.proj_endpoint1.w));
+                            segment_map
+                                .insert("end".into(), Dynamic::from(segment.proj_endpoint2.w));
+                            segment_map
+                                .insert("status".into(), range_status_name(segment.status).into());
+
+                            let color: Dynamic = call_fn(
+                                &self.callback_engine,
+                                &ast,
+                                "seam_color",
+                                (seam.clone(), Dynamic::from_map(segment_map)),
+                            )?;
+                            parse_color(color)
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    cache.insert(seam_info.seam.clone(), (keys, colors.clone()));
+                    colors
+                }
+            };
+
+            for (segment, color) in seam_info.segments.iter_mut().zip(colors) {
+                segment.custom_color = color;
+            }
+            Ok::<(), Box<EvalAltResult>>(())
+        });
+
+        if let Err(error) = result {
+            let error = format!("{}: seam_color: {}", self.scripts[script_index].name, error);
+            log::error!("Script error in {}", error);
+            self.errors.push(error);
+            self.coloring_script = None;
+        }
+    }
+
+    pub fn is_job_running(&self) -> bool {
+        self.job.lock().unwrap().running.is_some()
+    }
+
+    /// Stop the running job at its next operation.
+    pub fn stop_job(&self) {
+        self.job_cancel.store(true, Ordering::Relaxed);
+    }
+
+    /// Run the script's `main` function on a background thread.
+    pub fn run_main(&self, script_index: usize) {
+        let script = &self.scripts[script_index];
+        {
+            let mut job = self.job.lock().unwrap();
+            job.running = Some(script.name.clone());
+            job.output.clear();
+        }
+        self.job_cancel.store(false, Ordering::Relaxed);
+
+        let engine = Arc::clone(&self.engine);
+        let ast = Arc::clone(&script.ast);
+        let job = Arc::clone(&self.job);
+        thread::spawn(move || {
+            let result = call_fn::<Dynamic>(&engine, &ast, "main", ());
+
+            let mut job = job.lock().unwrap();
+            match result {
+                Ok(_) => job.print("Finished".to_owned()),
+                Err(error) => job.print(format!("Error: {}", error)),
+            }
+            job.running = None;
+        });
+    }
+}
+
+fn call_fn<T: Clone + Send + Sync + 'static>(
+    engine: &Engine,
+    ast: &AST,
+    name: &str,
+    args: impl rhai::FuncArgs,
+) -> Result<T, Box<EvalAltResult>> {
+    // Top level statements are only evaluated when the script is first run
+    let options = CallFnOptions::new().eval_ast(false);
+    engine.call_fn_with_options(options, &mut Scope::new(), ast, name, args)
+}
+
+fn create_engine(
+    context: &Arc<Mutex<ScriptContext>>,
+    job: &Arc<Mutex<ScriptJob>>,
+    job_cancel: &Arc<AtomicBool>,
+) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_call_levels(64);
+    engine.set_max_expr_depths(64, 32);
+    engine.set_max_string_size(1 << 20);
+    engine.set_max_array_size(1 << 20);
+    engine.set_max_map_size(1 << 16);
+
+    let job2 = Arc::clone(job);
+    engine.on_print(move |text| job2.lock().unwrap().print(text.to_owned()));
+    let job2 = Arc::clone(job);
+    engine.on_debug(move |text, _, _| job2.lock().unwrap().print(text.to_owned()));
+
+    let context2 = Arc::clone(context);
+    engine.register_fn("seams", move || -> Result<Array, Box<EvalAltResult>> {
+        let context = context2.lock().unwrap();
+        context.seams.iter().map(to_dynamic).collect()
+    });
+
+    let context2 = Arc::clone(context);
+    engine.register_fn("surfaces", move || -> Result<Array, Box<EvalAltResult>> {
+        let context = context2.lock().unwrap();
+        context.surfaces.iter().map(to_dynamic).collect()
+    });
+
+    let context2 = Arc::clone(context);
+    engine.register_fn("filter", move || {
+        context2.lock().unwrap().filter.to_string()
+    });
+
+    engine.register_fn(
+        "check_point",
+        |seam: Dynamic, w: FLOAT, filter: &str| -> Result<Map, Box<EvalAltResult>> {
+            let seam: Seam = from_dynamic(&seam)?;
+            let filter = parse_point_filter(filter)?;
+            Ok(check_point(&seam, w, &filter))
+        },
+    );
+
+    let context2 = Arc::clone(context);
+    engine.register_fn(
+        "check_point",
+        move |seam: Dynamic, w: FLOAT| -> Result<Map, Box<EvalAltResult>> {
+            let seam: Seam = from_dynamic(&seam)?;
+            let filter = context2.lock().unwrap().filter.clone();
+            Ok(check_point(&seam, w, &filter))
+        },
+    );
+
+    let job_cancel = Arc::clone(job_cancel);
+    engine.register_fn(
+        "export_seam",
+        move |seam: Dynamic,
+              filename: &str,
+              point_filter: &str,
+              status_filter: &str,
+              include_small_w: bool|
+              -> Result<(), Box<EvalAltResult>> {
+            let seam: Seam = from_dynamic(&seam)?;
+            let options = ExportOptions {
+                point_filter: parse_point_filter(point_filter)?,
+                status_filter: from_dynamic(&status_filter.into())?,
+                include_small_w,
+                w_range: seam.w_range(),
+            };
+
+            let mut writer = BufWriter::new(File::create(filename).map_err(|e| e.to_string())?);
+            save_seam_to_csv(&mut writer, |_| {}, &job_cancel, &seam, &options)
+                .map_err(|e| e.to_string())?;
+            Ok(())
+        },
+    );
+
+    engine
+}
+
+fn parse_point_filter(name: &str) -> Result<PointFilter, Box<EvalAltResult>> {
+    PointFilter::from_name(name).ok_or_else(|| format!("unknown point filter: {}", name).into())
+}
+
+fn check_point(seam: &Seam, w: f32, filter: &PointFilter) -> Map {
+    let (y, status) = seam.check_point(w, filter);
+    let mut result = Map::new();
+    result.insert("w".into(), Dynamic::from(w));
+    result.insert("y".into(), Dynamic::from(y));
+    let position: Array = seam.point_at(w, y).into_iter().map(Dynamic::from).collect();
+    result.insert("position".into(), position.into());
+    result.insert("status".into(), status.to_string().into());
+    result
+}
+
+fn range_status_name(status: RangeStatus) -> &'static str {
+    match status {
+        RangeStatus::Checked {
+            has_gap: false,
+            has_overlap: false,
+        } => "none",
+        RangeStatus::Checked {
+            has_gap: true,
+            has_overlap: false,
+        } => "gap",
+        RangeStatus::Checked {
+            has_gap: false,
+            has_overlap: true,
+        } => "overlap",
+        RangeStatus::Checked {
+            has_gap: true,
+            has_overlap: true,
+        } => "gap_and_overlap",
+        RangeStatus::Unchecked => "unchecked",
+        RangeStatus::Skipped => "skipped",
+    }
+}
+
+fn parse_color(color: Dynamic) -> Result<Option<[f32; 4]>, Box<EvalAltResult>> {
+    if color.is_unit() {
+        return Ok(None);
+    }
+
+    let components: Vec<FLOAT> = from_dynamic(&color)?;
+    match components[..] {
+        [r, g, b] => Ok(Some([r, g, b, 1.0])),
+        [r, g, b, a] => Ok(Some([r, g, b, a])),
+        _ => Err("seam_color must return [r, g, b] or [r, g, b, a]".into()),
+    }
+}
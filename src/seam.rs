@@ -1,21 +1,78 @@
 use crate::{
-    edge::{Edge, ProjectedPoint},
+    edge::{Edge, ProjectedPoint, ProjectionAxis},
     float_range::{RangeF32, next_f32, prev_f32},
     geo::Point3f,
 };
-use std::fmt::{self, Display};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::{self, Display},
+    hash::{Hash, Hasher},
+    str::FromStr,
+    sync::{Arc, RwLock},
+};
+
+pub type CustomFilterFn = dyn Fn(ProjectedPoint<f32>) -> bool + Send + Sync;
+
+/// Point filters defined at runtime (e.g. by scripts), listed by `PointFilter::all`.
+static CUSTOM_FILTERS: RwLock<Vec<CustomFilter>> = RwLock::new(Vec::new());
+
+/// Replace the set of custom point filters.
+///
+/// Existing `PointFilter::Custom` values keep using the filter they were created with.
+pub fn set_custom_filters(filters: Vec<(String, Arc<CustomFilterFn>)>) {
+    *CUSTOM_FILTERS.write().unwrap() = filters
+        .into_iter()
+        .map(|(name, filter)| CustomFilter {
+            name: name.into(),
+            filter,
+        })
+        .collect();
+}
+
+/// A point filter defined at runtime, e.g. by a script.
+///
+/// Two custom filters are equal only if they share the same function, so a filter from before
+/// the scripts were reloaded is distinct from its replacement.
+#[derive(Clone)]
+pub struct CustomFilter {
+    name: Arc<str>,
+    filter: Arc<CustomFilterFn>,
+}
+
+impl CustomFilter {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl fmt::Debug for CustomFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("CustomFilter").field(&self.name).finish()
+    }
+}
+
+impl PartialEq for CustomFilter {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.filter, &other.filter)
+    }
+}
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+impl Eq for CustomFilter {}
+
+impl Hash for CustomFilter {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Arc::as_ptr(&self.filter).cast::<()>().hash(state);
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(into = "PointFilterName", try_from = "PointFilterName")]
 pub enum PointFilter {
+    #[default]
     None,
     IntY,
     QuarterIntY,
-}
-
-impl Default for PointFilter {
-    fn default() -> Self {
-        Self::None
-    }
+    Custom(CustomFilter),
 }
 
 impl Display for PointFilter {
@@ -24,13 +81,25 @@ impl Display for PointFilter {
             PointFilter::None => write!(f, "all y"),
             PointFilter::IntY => write!(f, "int y"),
             PointFilter::QuarterIntY => write!(f, "qint y"),
+            PointFilter::Custom(custom) => write!(f, "{}", custom.name),
         }
     }
 }
 
 impl PointFilter {
     pub fn all() -> Vec<Self> {
-        vec![Self::None, Self::IntY, Self::QuarterIntY]
+        let custom_filters = CUSTOM_FILTERS.read().unwrap();
+        [Self::None, Self::IntY, Self::QuarterIntY]
+            .into_iter()
+            .chain(custom_filters.iter().cloned().map(Self::Custom))
+            .collect()
+    }
+
+    /// Find a filter by its display name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::all()
+            .into_iter()
+            .find(|filter| filter.to_string() == name)
     }
 
     pub fn matches(&self, point: ProjectedPoint<f32>) -> bool {
@@ -38,11 +107,54 @@ impl PointFilter {
             PointFilter::None => true,
             PointFilter::IntY => point.y.fract() == 0.0,
             PointFilter::QuarterIntY => [0.0, 0.25, 0.5, 0.75].contains(&point.y.fract()),
+            PointFilter::Custom(custom) => (custom.filter)(point),
         }
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// How a `PointFilter` is serialized. Custom filters are stored by name and looked up among the
+/// currently loaded filters.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum PointFilterName {
+    None,
+    IntY,
+    QuarterIntY,
+    Custom(String),
+}
+
+impl From<PointFilter> for PointFilterName {
+    fn from(filter: PointFilter) -> Self {
+        match filter {
+            PointFilter::None => Self::None,
+            PointFilter::IntY => Self::IntY,
+            PointFilter::QuarterIntY => Self::QuarterIntY,
+            PointFilter::Custom(custom) => Self::Custom(custom.name.to_string()),
+        }
+    }
+}
+
+impl TryFrom<PointFilterName> for PointFilter {
+    type Error = String;
+
+    fn try_from(name: PointFilterName) -> Result<Self, Self::Error> {
+        match name {
+            PointFilterName::None => Ok(Self::None),
+            PointFilterName::IntY => Ok(Self::IntY),
+            PointFilterName::QuarterIntY => Ok(Self::QuarterIntY),
+            PointFilterName::Custom(name) => CUSTOM_FILTERS
+                .read()
+                .unwrap()
+                .iter()
+                .find(|custom| *custom.name == name)
+                .map(|custom| Self::Custom(custom.clone()))
+                .ok_or_else(|| format!("point filter {} is not loaded", name)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum PointStatus {
     Gap,
     Overlap,
@@ -59,7 +171,21 @@ impl Display for PointStatus {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+impl FromStr for PointStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "gap" => Ok(PointStatus::Gap),
+            "overlap" => Ok(PointStatus::Overlap),
+            "none" => Ok(PointStatus::None),
+            _ => Err(format!("invalid point status: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum PointStatusFilter {
     GapsOnly,
     OverlapsOnly,
@@ -100,19 +226,59 @@ impl Display for PointStatusFilter {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum RangeStatus {
     Checked { has_gap: bool, has_overlap: bool },
     Unchecked,
     Skipped,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// The plane of a wall, containing the points `p` with `normal · p + origin_offset = 0`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WallPlane {
+    pub normal: [f32; 3],
+    pub origin_offset: f32,
+}
+
+impl WallPlane {
+    /// Compute the plane through `vertex` the same way the game computes `originOffset`.
+    pub fn new(normal: [f32; 3], vertex: [i16; 3]) -> Self {
+        let [x, y, z] = vertex.map(f32::from);
+        Self {
+            normal,
+            origin_offset: -(normal[0] * x + normal[1] * y + normal[2] * z),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Seam {
     pub edge1: Edge,
     pub edge2: Edge,
     /// For visualization
     pub endpoints: ([i16; 3], [i16; 3]),
+    /// The plane of the first wall, used to compute 3D positions. Missing for seams saved by
+    /// older versions.
+    #[serde(default)]
+    pub plane1: Option<WallPlane>,
+}
+
+// The plane is ignored, since it is determined by the edges and is missing from older saved seams
+impl PartialEq for Seam {
+    fn eq(&self, other: &Self) -> bool {
+        self.edge1 == other.edge1 && self.edge2 == other.edge2 && self.endpoints == other.endpoints
+    }
+}
+
+impl Eq for Seam {}
+
+impl Hash for Seam {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.edge1.hash(state);
+        self.edge2.hash(state);
+        self.endpoints.hash(state);
+    }
 }
 
 impl Seam {
@@ -136,6 +302,7 @@ impl Seam {
                 edge1,
                 edge2,
                 endpoints: vertices1,
+                plane1: Some(WallPlane::new(normal1, vertices1.0)),
             };
 
             // Simplifying assumption
@@ -153,7 +320,7 @@ impl Seam {
         self.edge1.w_range().intersect(&self.edge2.w_range())
     }
 
-    pub fn check_point(&self, w: f32, filter: PointFilter) -> (f32, PointStatus) {
+    pub fn check_point(&self, w: f32, filter: &PointFilter) -> (f32, PointStatus) {
         let y_approx = self.edge1.approx_y(w);
 
         let mut seen_in1 = false;
@@ -201,29 +368,28 @@ impl Seam {
         (y_approx, PointStatus::None)
     }
 
-    pub fn check_range(&self, w_range: RangeF32, filter: PointFilter) -> (usize, RangeStatus) {
-        let mut has_gap = false;
-        let mut has_overlap = false;
-        let mut num_interesting_points = 0;
+    pub fn check_range(&self, w_range: RangeF32, filter: &PointFilter) -> (usize, RangeStatus) {
+        let (num_gaps, num_overlaps) = self.count_range(w_range, filter);
+        (num_gaps + num_overlaps, RangeStatus::Checked {
+            has_gap: num_gaps > 0,
+            has_overlap: num_overlaps > 0,
+        })
+    }
+
+    /// Count the gaps and overlaps in a range of w values, returning `(num_gaps, num_overlaps)`.
+    pub fn count_range(&self, w_range: RangeF32, filter: &PointFilter) -> (usize, usize) {
+        let mut num_gaps = 0;
+        let mut num_overlaps = 0;
 
         for w in w_range.iter() {
             match self.check_point(w, filter).1 {
-                PointStatus::Gap => {
-                    has_gap = true;
-                    num_interesting_points += 1;
-                }
-                PointStatus::Overlap => {
-                    has_overlap = true;
-                    num_interesting_points += 1;
-                }
+                PointStatus::Gap => num_gaps += 1,
+                PointStatus::Overlap => num_overlaps += 1,
                 PointStatus::None => {}
             }
         }
 
-        (num_interesting_points, RangeStatus::Checked {
-            has_gap,
-            has_overlap,
-        })
+        (num_gaps, num_overlaps)
     }
 
     pub fn approx_point_at_w(&self, w: f32) -> [f32; 3] {
@@ -239,6 +405,28 @@ impl Seam {
         [x1 + t * (x2 - x1), y1 + t * (y2 - y1), z1 + t * (z2 - z1)]
     }
 
+    /// The 3D point with the given w and y on the first wall's plane.
+    ///
+    /// If the plane is unknown, the remaining coordinate is taken from the seam's edge instead.
+    pub fn point_at(&self, w: f32, y: f32) -> [f32; 3] {
+        let Some(WallPlane {
+            normal: [nx, ny, nz],
+            origin_offset,
+        }) = self.plane1
+        else {
+            let [x, _, z] = self.approx_point_at_w(w);
+            return match self.edge1.projection_axis {
+                ProjectionAxis::X => [x, y, w],
+                ProjectionAxis::Z => [w, y, z],
+            };
+        };
+
+        match self.edge1.projection_axis {
+            ProjectionAxis::X => [-(ny * y + nz * w + origin_offset) / nx, y, w],
+            ProjectionAxis::Z => [w, y, -(nx * w + ny * y + origin_offset) / nz],
+        }
+    }
+
     pub fn endpoint1(&self) -> Point3f {
         Point3f::new(
             self.endpoints.0[0] as f32,
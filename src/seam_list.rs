@@ -0,0 +1,175 @@
+use crate::{
+    edge::ProjectionAxis,
+    seam::{RangeStatus, Seam},
+    seam_processor::SeamProcessor,
+};
+use std::cmp::Ordering;
+
+/// How far outside a seam's bounding box a coordinate search can be and still match it.
+const SEARCH_MARGIN: f32 = 1.0;
+
+/// A column in the seam list, used for sorting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeamListColumn {
+    Endpoint1,
+    Endpoint2,
+    Axis,
+    Length,
+    Progress,
+    Gap,
+    Overlap,
+}
+
+impl SeamListColumn {
+    pub fn all() -> Vec<Self> {
+        vec![
+            Self::Endpoint1,
+            Self::Endpoint2,
+            Self::Axis,
+            Self::Length,
+            Self::Progress,
+            Self::Gap,
+            Self::Overlap,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Endpoint1 => "Endpoint 1",
+            Self::Endpoint2 => "Endpoint 2",
+            Self::Axis => "Axis",
+            Self::Length => "Length",
+            Self::Progress => "Progress",
+            Self::Gap => "Gap",
+            Self::Overlap => "Overlap",
+        }
+    }
+}
+
+/// A row in the seam list.
+#[derive(Debug, Clone)]
+pub struct SeamListRow {
+    pub seam: Seam,
+    pub axis: ProjectionAxis,
+    pub length: f32,
+    /// The fraction of the seam's w range that has been checked, from 0 to 1.
+    pub progress: f32,
+    pub has_gap: bool,
+    pub has_overlap: bool,
+}
+
+impl SeamListRow {
+    pub fn new(seam: &Seam, seam_processor: &SeamProcessor) -> Self {
+        let mut checked = 0.0;
+        let mut total = 0.0;
+        let mut has_gap = false;
+        let mut has_overlap = false;
+        for (range, status) in seam_processor.seam_progress(seam).segments() {
+            let length = range.end as f64 - range.start as f64;
+            total += length;
+            match status {
+                RangeStatus::Checked {
+                    has_gap: gap,
+                    has_overlap: overlap,
+                } => {
+                    checked += length;
+                    has_gap |= gap;
+                    has_overlap |= overlap;
+                }
+                RangeStatus::Skipped => checked += length,
+                RangeStatus::Unchecked => {}
+            }
+        }
+
+        let (endpoint1, endpoint2) = seam.endpoints;
+        let length = (0..3)
+            .map(|i| (endpoint2[i] as f32 - endpoint1[i] as f32).powi(2))
+            .sum::<f32>()
+            .sqrt();
+
+        Self {
+            seam: seam.clone(),
+            axis: seam.edge1.projection_axis,
+            length,
+            progress: if total > 0.0 {
+                (checked / total) as f32
+            } else {
+                1.0
+            },
+            has_gap,
+            has_overlap,
+        }
+    }
+
+    pub fn endpoint_text(endpoint: [i16; 3]) -> String {
+        format!("({}, {}, {})", endpoint[0], endpoint[1], endpoint[2])
+    }
+
+    pub fn progress_text(&self) -> String {
+        if self.progress >= 1.0 {
+            "complete".to_owned()
+        } else {
+            format!("{:.0}%", self.progress * 100.0)
+        }
+    }
+
+    /// Return true if the row matches the search query.
+    ///
+    /// A query consisting of two numbers `x z` or three numbers `x y z` (separated by spaces or
+    /// commas) matches seams whose bounding box contains (or nearly contains) that point. Any
+    /// other query is matched case insensitively against the text shown in the row.
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.trim();
+        if query.is_empty() {
+            return true;
+        }
+
+        let coords: Option<Vec<f32>> = query
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse().ok())
+            .collect();
+        match coords.as_deref() {
+            Some(&[x, z]) => return self.contains(&[Some(x), None, Some(z)]),
+            Some(&[x, y, z]) => return self.contains(&[Some(x), Some(y), Some(z)]),
+            _ => {}
+        }
+
+        let query = query.to_lowercase();
+        let (endpoint1, endpoint2) = self.seam.endpoints;
+        [
+            Self::endpoint_text(endpoint1),
+            Self::endpoint_text(endpoint2),
+            self.axis.to_string(),
+            self.progress_text(),
+            if self.has_gap { "gap" } else { "" }.to_owned(),
+            if self.has_overlap { "overlap" } else { "" }.to_owned(),
+        ]
+        .iter()
+        .any(|text| text.contains(&query))
+    }
+
+    fn contains(&self, point: &[Option<f32>; 3]) -> bool {
+        let (endpoint1, endpoint2) = self.seam.endpoints;
+        (0..3).all(|i| match point[i] {
+            Some(value) => {
+                let min = endpoint1[i].min(endpoint2[i]) as f32 - SEARCH_MARGIN;
+                let max = endpoint1[i].max(endpoint2[i]) as f32 + SEARCH_MARGIN;
+                value >= min && value <= max
+            }
+            None => true,
+        })
+    }
+
+    pub fn compare(&self, other: &Self, column: SeamListColumn) -> Ordering {
+        match column {
+            SeamListColumn::Endpoint1 => self.seam.endpoints.0.cmp(&other.seam.endpoints.0),
+            SeamListColumn::Endpoint2 => self.seam.endpoints.1.cmp(&other.seam.endpoints.1),
+            SeamListColumn::Axis => self.axis.to_string().cmp(&other.axis.to_string()),
+            SeamListColumn::Length => self.length.total_cmp(&other.length),
+            SeamListColumn::Progress => self.progress.total_cmp(&other.progress),
+            SeamListColumn::Gap => self.has_gap.cmp(&other.has_gap),
+            SeamListColumn::Overlap => self.has_overlap.cmp(&other.has_overlap),
+        }
+    }
+}
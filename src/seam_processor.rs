@@ -6,6 +6,7 @@ use crate::{
     spatial_partition::SpatialPartition,
 };
 use rayon::prelude::*;
+use serde::Serialize;
 use std::{
     collections::{HashMap, VecDeque},
     iter,
@@ -63,7 +64,7 @@ pub struct SeamPoints {
     pub points: Vec<(ProjectedPoint<f32>, PointStatus)>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct SeamProgress {
     segment_length: f32,
     complete: Vec<(RangeF32, RangeStatus)>,
@@ -86,7 +87,7 @@ impl SeamProgress {
             .chain(iter::once((self.remaining, RangeStatus::Unchecked)))
     }
 
-    fn is_complete(&self) -> bool {
+    pub fn is_complete(&self) -> bool {
         self.remaining.is_empty()
     }
 
@@ -147,7 +148,8 @@ pub struct SeamProcessor {
     progress: HashMap<Seam, SeamProgress>,
     queue: Arc<Mutex<VecDeque<SeamRequest>>>,
     output_receiver: Receiver<(SeamRequest, SeamOutput)>,
-    focused_seam: Option<(SeamRequest, SeamOutput)>,
+    /// The latest request and output for each open seam view, keyed by the seam view's id.
+    focused_seams: HashMap<usize, (SeamRequest, SeamOutput)>,
     filter: PointFilter,
 }
 
@@ -164,7 +166,7 @@ impl SeamProcessor {
             progress: HashMap::new(),
             queue,
             output_receiver: receiver,
-            focused_seam: None,
+            focused_seams: HashMap::new(),
             filter: PointFilter::None,
         }
     }
@@ -229,23 +231,22 @@ impl SeamProcessor {
             if queue.is_empty() {
                 for seam in &self.active_seams {
                     if !self.progress.contains_key(seam) {
-                        queue.push_back(SeamRequest::unfocused(seam.clone(), self.filter));
+                        queue.push_back(SeamRequest::unfocused(seam.clone(), self.filter.clone()));
                     }
                 }
             }
         }
 
         while let Ok((request, progress)) = self.output_receiver.try_recv() {
-            if request.filter != self.filter {
-                continue;
-            }
-
             if request.is_focused {
-                if let Some((focused_request, _)) = &self.focused_seam {
+                // Seam views can have their own filter
+                for (focused_request, focused_progress) in self.focused_seams.values_mut() {
                     if focused_request == &request {
-                        self.focused_seam = Some((request, progress));
+                        *focused_progress = progress.clone();
                     }
                 }
+            } else if request.filter != self.filter {
+                continue;
             } else if let SeamOutput::Segments(progress) = progress {
                 self.progress.insert(request.seam, progress);
             } else {
@@ -256,15 +257,18 @@ impl SeamProcessor {
 
     pub fn focused_seam_progress(
         &mut self,
+        view_id: usize,
         seam: &Seam,
         w_range: RangeF32,
         segment_length: f32,
+        filter: PointFilter,
     ) -> SeamOutput {
-        let request = SeamRequest::focused(seam.clone(), w_range, segment_length, self.filter);
+        let request = SeamRequest::focused(seam.clone(), w_range, segment_length, filter.clone());
         let mut progress = SeamOutput::Segments(SeamProgress::new(w_range, segment_length));
 
-        if let Some((focused_request, focused_progress)) = &self.focused_seam {
-            if &focused_request.seam == seam {
+        let prev_request = self.focused_seams.get(&view_id);
+        if let Some((focused_request, focused_progress)) = prev_request {
+            if &focused_request.seam == seam && focused_request.filter == filter {
                 progress = focused_progress.clone();
                 // TODO: Extend progress range
                 // let total_range = progress.total_range();
@@ -287,17 +291,41 @@ impl SeamProcessor {
             }
         }
 
-        self.focused_seam = Some((request.clone(), progress.clone()));
-
         {
+            // Clear the queued focused requests so that stale ones are dropped, then requeue this
+            // view's request first, followed by the other views' requests that were still queued
             let mut queue = self.queue.lock().unwrap();
-            queue.clear();
-            queue.push_back(request);
+            let other_requests: Vec<SeamRequest> = queue
+                .iter()
+                .filter(|queued| {
+                    self.focused_seams
+                        .iter()
+                        .any(|(id, (other, _))| *id != view_id && other == *queued)
+                })
+                .cloned()
+                .collect();
+            queue.retain(|queued| !queued.is_focused);
+            for other_request in other_requests.into_iter().rev() {
+                queue.push_front(other_request);
+            }
+            queue.push_front(request.clone());
         }
 
+        self.focused_seams
+            .insert(view_id, (request, progress.clone()));
         progress
     }
 
+    /// Stop processing the seam shown in a seam view that has been closed.
+    pub fn remove_focused_seam(&mut self, view_id: usize) {
+        if let Some((request, _)) = self.focused_seams.remove(&view_id) {
+            self.queue
+                .lock()
+                .unwrap()
+                .retain(|queued| queued != &request);
+        }
+    }
+
     pub fn active_seams(&self) -> &[Seam] {
         &self.active_seams
     }
@@ -316,15 +344,16 @@ impl SeamProcessor {
             .unwrap_or(SeamProgress::new(seam.w_range(), DEFAULT_SEGMENT_LENGTH))
     }
 
-    pub fn filter(&self) -> PointFilter {
-        self.filter
+    pub fn filter(&self) -> &PointFilter {
+        &self.filter
     }
 
     pub fn set_filter(&mut self, filter: PointFilter) {
         self.filter = filter;
-        self.focused_seam = None;
         self.progress.clear();
         self.queue.lock().unwrap().clear();
+        // The focused requests were removed from the queue, so they need to be requested again
+        self.focused_seams.clear();
     }
 }
 
@@ -344,7 +373,12 @@ fn processor_thread(
 
             let segment_statuses: Vec<(RangeF32, (usize, RangeStatus))> = segments
                 .par_iter()
-                .map(|segment| (*segment, request.seam.check_range(*segment, request.filter)))
+                .map(|segment| {
+                    (
+                        *segment,
+                        request.seam.check_range(*segment, &request.filter),
+                    )
+                })
                 .collect();
 
             let num_interesting_points: usize = segment_statuses
@@ -359,7 +393,7 @@ fn processor_thread(
                         segment
                             .iter()
                             .map(|w| {
-                                let (y, status) = request.seam.check_point(w, request.filter);
+                                let (y, status) = request.seam.check_point(w, &request.filter);
                                 (ProjectedPoint { w, y }, status)
                             })
                             .filter(|(_, status)| *status != PointStatus::None)
@@ -380,3 +414,101 @@ fn processor_thread(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_seam(x: i16) -> Seam {
+        Seam::between(
+            ([x, 0, 0], [x + 100, 37, 0]),
+            [0.0, 0.0, 1.0],
+            ([x + 100, 37, 0], [x, 0, 0]),
+            [0.0, 0.0, 1.0],
+        )
+        .unwrap()
+    }
+
+    /// A processor without a processing thread, so that the queue can be inspected.
+    fn test_processor() -> SeamProcessor {
+        SeamProcessor {
+            active_seams: Vec::new(),
+            progress: HashMap::new(),
+            queue: Arc::new(Mutex::new(VecDeque::new())),
+            output_receiver: channel().1,
+            focused_seams: HashMap::new(),
+            filter: PointFilter::None,
+        }
+    }
+
+    fn queued_seams(processor: &SeamProcessor) -> Vec<(Seam, bool)> {
+        processor
+            .queue
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|request| (request.seam.clone(), request.is_focused))
+            .collect()
+    }
+
+    #[test]
+    fn focused_requests_are_queued_first() {
+        let mut processor = test_processor();
+        let (seam1, seam2, seam3) = (test_seam(0), test_seam(200), test_seam(400));
+        let range1 = RangeF32::inclusive(10.0, 20.0);
+        let range2 = RangeF32::inclusive(30.0, 40.0);
+        let filter = PointFilter::None;
+
+        processor
+            .queue
+            .lock()
+            .unwrap()
+            .push_back(SeamRequest::unfocused(seam3.clone(), filter.clone()));
+        processor.focused_seam_progress(1, &seam1, range1, 1.0, filter.clone());
+        processor.focused_seam_progress(2, &seam2, range1, 1.0, filter.clone());
+        assert_eq!(
+            queued_seams(&processor),
+            [
+                (seam2.clone(), true),
+                (seam1.clone(), true),
+                (seam3.clone(), false)
+            ],
+        );
+
+        // Requesting the same range again doesn't requeue it
+        processor.focused_seam_progress(1, &seam1, range1, 1.0, filter.clone());
+        assert_eq!(queued_seams(&processor)[0], (seam2.clone(), true));
+
+        // A stale focused request is dropped, and the view that moved goes first
+        processor
+            .queue
+            .lock()
+            .unwrap()
+            .push_back(SeamRequest::focused(
+                seam3.clone(),
+                range1,
+                1.0,
+                filter.clone(),
+            ));
+        processor.focused_seam_progress(1, &seam1, range2, 1.0, filter.clone());
+        let queue = processor.queue.lock().unwrap().clone();
+        assert_eq!(queue.len(), 3);
+        assert_eq!(queue[0].w_range, range2);
+        assert_eq!(queue[1].seam, seam2);
+        assert_eq!(
+            queue[2],
+            SeamRequest::unfocused(seam3.clone(), filter.clone())
+        );
+
+        // Requests that the processing thread already took are not requeued
+        processor.queue.lock().unwrap().remove(1);
+        processor.focused_seam_progress(1, &seam1, range1, 1.0, filter.clone());
+        assert_eq!(
+            queued_seams(&processor),
+            [(seam1.clone(), true), (seam3.clone(), false)],
+        );
+
+        processor.remove_focused_seam(1);
+        assert_eq!(queued_seams(&processor), [(seam3, false)]);
+    }
+}
@@ -0,0 +1,562 @@
+use crate::{
+    game_state::{GameState, Surface},
+    seam::{PointFilter, Seam},
+    seam_processor::{SeamProcessor, SeamProgress},
+};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::{
+    collections::HashMap,
+    io::{self, BufRead, BufReader, Write},
+    net::{Shutdown, SocketAddr, TcpListener, TcpStream},
+    sync::{
+        Arc, Condvar, Mutex, MutexGuard,
+        atomic::{AtomicBool, Ordering},
+        mpsc::{Receiver, SyncSender, TrySendError, sync_channel},
+    },
+    thread,
+    time::Duration,
+};
+
+pub const DEFAULT_SERVER_ADDR: &str = "127.0.0.1:7164";
+
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// The maximum number of notifications waiting to be sent to subscribers.
+///
+/// If subscribers fall behind, newer notifications are dropped rather than queued without bound,
+/// and a `resync` notification is sent once there is room again.
+const MAX_PENDING_NOTIFICATIONS: usize = 64;
+/// How long a subscriber can block a notification before it is disconnected.
+const SUBSCRIBER_WRITE_TIMEOUT: Duration = Duration::from_secs(1);
+/// How long a request waits for the UI thread to refresh the snapshot before using the old one.
+const SNAPSHOT_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// The data exposed to clients, copied from the UI thread when a request or subscriber needs it.
+#[derive(Debug, Default)]
+struct Snapshot {
+    /// Incremented each time the snapshot is refreshed.
+    version: u64,
+    seams: Vec<Seam>,
+    progress: Vec<SeamProgress>,
+    remaining: usize,
+    filter: PointFilter,
+    surfaces: Vec<Surface>,
+}
+
+#[derive(Debug)]
+struct Shared {
+    snapshot: Mutex<Snapshot>,
+    snapshot_updated: Condvar,
+    /// Set by a connection that is waiting for a fresh snapshot.
+    snapshot_wanted: AtomicBool,
+    subscribers: Mutex<Vec<Arc<TcpStream>>>,
+    /// Open connections, so that they can be closed when the server is stopped.
+    connections: Mutex<HashMap<SocketAddr, TcpStream>>,
+    shutdown: AtomicBool,
+}
+
+/// A local JSON-RPC 2.0 server.
+///
+/// Requests and responses are newline-delimited JSON objects sent over a TCP connection.
+/// Supported methods:
+/// - `active_seams`: the list of active seams
+/// - `seam_progress {seam}`: the checked segments of the seam with the given index
+/// - `check_point {seam, w, filter?}`: the status and 3D position of a single point on a seam
+/// - `surfaces`: the current surface pool
+/// - `subscribe`: receive `progress` notifications whenever a seam's progress changes, and a
+///   `resync` notification if some were dropped
+#[derive(Debug)]
+pub struct Server {
+    addr: SocketAddr,
+    shared: Arc<Shared>,
+    notifications: SyncSender<Value>,
+    /// Set when a notification was dropped because the queue was full.
+    needs_resync: AtomicBool,
+}
+
+impl Server {
+    pub fn start(addr: &str) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        let addr = listener.local_addr()?;
+
+        let shared = Arc::new(Shared {
+            snapshot: Mutex::new(Snapshot::default()),
+            snapshot_updated: Condvar::new(),
+            snapshot_wanted: AtomicBool::new(false),
+            subscribers: Mutex::new(Vec::new()),
+            connections: Mutex::new(HashMap::new()),
+            shutdown: AtomicBool::new(false),
+        });
+
+        let shared2 = Arc::clone(&shared);
+        thread::spawn(move || listener_thread(listener, shared2));
+
+        let (sender, receiver) = sync_channel(MAX_PENDING_NOTIFICATIONS);
+        let shared2 = Arc::clone(&shared);
+        thread::spawn(move || notifier_thread(receiver, shared2));
+
+        log::info!("Server listening on {}", addr);
+        Ok(Self {
+            addr,
+            shared,
+            notifications: sender,
+            needs_resync: AtomicBool::new(false),
+        })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    fn notify(&self, notification: Value) {
+        if self.needs_resync.load(Ordering::Relaxed) {
+            let resync = json!({ "jsonrpc": "2.0", "method": "resync" });
+            if self.notifications.try_send(resync).is_err() {
+                return;
+            }
+            self.needs_resync.store(false, Ordering::Relaxed);
+        }
+
+        if let Err(TrySendError::Full(_)) = self.notifications.try_send(notification) {
+            log::warn!("Server dropped a notification because subscribers are falling behind");
+            self.needs_resync.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Refresh the snapshot if a connection is waiting for it, and notify subscribers of any
+    /// progress changes.
+    pub fn update(&self, state: &GameState, seam_processor: &SeamProcessor) {
+        let has_subscribers = !self.shared.subscribers.lock().unwrap().is_empty();
+        let wanted = self.shared.snapshot_wanted.swap(false, Ordering::SeqCst);
+        if !has_subscribers && !wanted {
+            return;
+        }
+
+        let seams = seam_processor.active_seams().to_vec();
+        let progress: Vec<SeamProgress> = seams
+            .iter()
+            .map(|seam| seam_processor.seam_progress(seam))
+            .collect();
+
+        let mut snapshot = self.shared.snapshot.lock().unwrap();
+
+        if has_subscribers {
+            let changed: Vec<usize> =
+                if snapshot.seams == seams && snapshot.filter == *seam_processor.filter() {
+                    (0..seams.len())
+                        .filter(|&index| snapshot.progress[index] != progress[index])
+                        .collect()
+                } else {
+                    (0..seams.len()).collect()
+                };
+
+            if !changed.is_empty() {
+                self.notify(json!({
+                    "jsonrpc": "2.0",
+                    "method": "progress",
+                    "params": {
+                        "remaining": seam_processor.remaining_seams(),
+                        "filter": seam_processor.filter(),
+                        "seams": changed
+                            .iter()
+                            .map(|&index| json!({
+                                "seam": index,
+                                "progress": progress[index],
+                            }))
+                            .collect::<Vec<_>>(),
+                    },
+                }));
+            }
+        }
+
+        snapshot.version += 1;
+        snapshot.seams = seams;
+        snapshot.progress = progress;
+        snapshot.remaining = seam_processor.remaining_seams();
+        snapshot.filter = seam_processor.filter().clone();
+        if snapshot.surfaces != state.surfaces {
+            snapshot.surfaces = state.surfaces.clone();
+        }
+        self.shared.snapshot_updated.notify_all();
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        self.shared.shutdown.store(true, Ordering::SeqCst);
+        self.shared.snapshot_updated.notify_all();
+        // Wake up the listener thread so that it can exit
+        let _ = TcpStream::connect(self.addr);
+        // Closing the connections also unblocks any write to a subscriber, since subscribers
+        // share their connection's socket
+        for (_, stream) in self.shared.connections.lock().unwrap().drain() {
+            let _ = stream.shutdown(Shutdown::Both);
+        }
+        self.shared.subscribers.lock().unwrap().clear();
+    }
+}
+
+fn listener_thread(listener: TcpListener, shared: Arc<Shared>) {
+    for stream in listener.incoming() {
+        if shared.shutdown.load(Ordering::SeqCst) {
+            break;
+        }
+        match stream {
+            Ok(stream) => {
+                let shared = Arc::clone(&shared);
+                thread::spawn(move || {
+                    let peer_addr = stream.peer_addr();
+                    if let Err(error) = connection_thread(stream, &shared) {
+                        log::warn!("Server connection closed: {}", error);
+                    }
+                    if let Ok(peer_addr) = peer_addr {
+                        shared.connections.lock().unwrap().remove(&peer_addr);
+                    }
+                });
+            }
+            Err(error) => log::warn!("Server failed to accept connection: {}", error),
+        }
+    }
+}
+
+fn notifier_thread(receiver: Receiver<Value>, shared: Arc<Shared>) {
+    while let Ok(notification) = receiver.recv() {
+        let line = format!("{}\n", notification);
+        // Write without holding the lock, so that a slow subscriber can't block the server
+        let subscribers = shared.subscribers.lock().unwrap().clone();
+        let failed: Vec<Arc<TcpStream>> = subscribers
+            .into_iter()
+            .filter(|stream| (&**stream).write_all(line.as_bytes()).is_err())
+            .collect();
+        if !failed.is_empty() {
+            shared
+                .subscribers
+                .lock()
+                .unwrap()
+                .retain(|stream| !failed.iter().any(|failed| Arc::ptr_eq(stream, failed)));
+        }
+    }
+}
+
+/// Wait for the UI thread to refresh the snapshot, so that requests see the current state.
+fn fresh_snapshot(shared: &Shared) -> MutexGuard<'_, Snapshot> {
+    let snapshot = shared.snapshot.lock().unwrap();
+    let version = snapshot.version;
+    shared.snapshot_wanted.store(true, Ordering::SeqCst);
+    let (snapshot, _) = shared
+        .snapshot_updated
+        .wait_timeout_while(snapshot, SNAPSHOT_TIMEOUT, |snapshot| {
+            snapshot.version == version && !shared.shutdown.load(Ordering::SeqCst)
+        })
+        .unwrap();
+    snapshot
+}
+
+fn connection_thread(stream: TcpStream, shared: &Shared) -> io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    shared
+        .connections
+        .lock()
+        .unwrap()
+        .insert(stream.peer_addr()?, stream.try_clone()?);
+    // The server may have been stopped before the connection was registered
+    if shared.shutdown.load(Ordering::SeqCst) {
+        return Ok(());
+    }
+
+    let reader = BufReader::new(stream);
+
+    for line in reader.lines() {
+        let line = line?;
+        if shared.shutdown.load(Ordering::SeqCst) {
+            break;
+        }
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let mut subscribe = false;
+        let response = match serde_json::from_str::<Value>(&line) {
+            Ok(request) => {
+                let id = request.get("id").cloned().unwrap_or(Value::Null);
+                let method = request.get("method").and_then(Value::as_str).unwrap_or("");
+                let params = request.get("params").cloned().unwrap_or(Value::Null);
+
+                subscribe = method == "subscribe";
+
+                match handle_request(shared, method, params) {
+                    Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+                    Err((code, message)) => json!({
+                        "jsonrpc": "2.0",
+                        "id": id,
+                        "error": { "code": code, "message": message },
+                    }),
+                }
+            }
+            Err(error) => json!({
+                "jsonrpc": "2.0",
+                "id": Value::Null,
+                "error": { "code": PARSE_ERROR, "message": error.to_string() },
+            }),
+        };
+
+        if subscribe {
+            writer.set_write_timeout(Some(SUBSCRIBER_WRITE_TIMEOUT))?;
+            // Hold the lock so that no notification is missed or sent before the response
+            let mut subscribers = shared.subscribers.lock().unwrap();
+            writeln!(writer, "{}", response)?;
+            subscribers.push(Arc::new(writer.try_clone()?));
+        } else {
+            writeln!(writer, "{}", response)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Deserialize)]
+struct SeamParams {
+    seam: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct CheckPointParams {
+    seam: usize,
+    w: f32,
+    filter: Option<PointFilter>,
+}
+
+fn parse_params<T: for<'de> Deserialize<'de>>(params: Value) -> Result<T, (i64, String)> {
+    serde_json::from_value(params).map_err(|error| (INVALID_PARAMS, error.to_string()))
+}
+
+fn handle_request(shared: &Shared, method: &str, params: Value) -> Result<Value, (i64, String)> {
+    if method == "subscribe" {
+        return Ok(json!(true));
+    }
+
+    let snapshot = fresh_snapshot(shared);
+
+    let get_seam = |index: usize| {
+        snapshot
+            .seams
+            .get(index)
+            .ok_or_else(|| (INVALID_PARAMS, format!("no seam with index {}", index)))
+    };
+
+    match method {
+        "active_seams" => Ok(json!({
+            "filter": snapshot.filter,
+            "remaining": snapshot.remaining,
+            "seams": snapshot.seams,
+        })),
+        "seam_progress" => {
+            let params: SeamParams = parse_params(params)?;
+            get_seam(params.seam)?;
+            Ok(json!(snapshot.progress[params.seam]))
+        }
+        "check_point" => {
+            let params: CheckPointParams = parse_params(params)?;
+            let seam = get_seam(params.seam)?;
+            let (y, status) = seam.check_point(
+                params.w,
+                &params.filter.unwrap_or_else(|| snapshot.filter.clone()),
+            );
+            Ok(json!({
+                "w": params.w,
+                "y": y,
+                "position": seam.point_at(params.w, y),
+                "status": status,
+            }))
+        }
+        "surfaces" => Ok(json!(snapshot.surfaces)),
+        _ => Err((METHOD_NOT_FOUND, format!("unknown method: {}", method))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two walls facing +z that share a sloped edge.
+    fn game_state() -> GameState {
+        let wall = |vertex1, vertex2, vertex3| Surface {
+            vertex1,
+            vertex2,
+            vertex3,
+            normal: [0.0, 0.0, 1.0],
+            ..Default::default()
+        };
+        GameState {
+            global_timer: 0,
+            lakitu_pos: [0.0; 3],
+            lakitu_focus: [0.0; 3],
+            mario: None,
+            surfaces: vec![
+                wall([0, 0, 0], [100, 100, 0], [0, 100, 0]),
+                wall([0, 0, 0], [100, 0, 0], [100, 100, 0]),
+            ],
+        }
+    }
+
+    struct Client {
+        reader: BufReader<TcpStream>,
+        writer: TcpStream,
+    }
+
+    impl Client {
+        fn connect(server: &Server) -> Self {
+            let writer = TcpStream::connect(server.addr()).unwrap();
+            Self {
+                reader: BufReader::new(writer.try_clone().unwrap()),
+                writer,
+            }
+        }
+
+        fn send(&mut self, request: &str) -> Value {
+            writeln!(self.writer, "{}", request).unwrap();
+            self.receive()
+        }
+
+        fn call(&mut self, method: &str, params: Value) -> Value {
+            self.send(
+                &json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params })
+                    .to_string(),
+            )
+        }
+
+        fn receive(&mut self) -> Value {
+            let mut line = String::new();
+            self.reader.read_line(&mut line).unwrap();
+            serde_json::from_str(&line).unwrap()
+        }
+    }
+
+    /// Set the flag when dropped, so that the updater thread stops even if a test fails.
+    struct StopOnDrop<'a>(&'a AtomicBool);
+
+    impl Drop for StopOnDrop<'_> {
+        fn drop(&mut self) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    /// Run a test against a server that is updated every few milliseconds, like the UI thread
+    /// does.
+    fn with_server(test: impl FnOnce(&Server)) {
+        let server = Server::start("127.0.0.1:0").unwrap();
+        let stop = AtomicBool::new(false);
+        thread::scope(|scope| {
+            scope.spawn(|| {
+                let state = game_state();
+                let mut seam_processor = SeamProcessor::new();
+                while !stop.load(Ordering::SeqCst) {
+                    seam_processor.update(&state);
+                    server.update(&state, &seam_processor);
+                    thread::sleep(Duration::from_millis(5));
+                }
+            });
+            let _stop = StopOnDrop(&stop);
+            test(&server);
+        });
+    }
+
+    #[test]
+    fn active_seams_and_check_point() {
+        with_server(|server| {
+            let mut client = Client::connect(server);
+
+            let response = client.call("active_seams", Value::Null);
+            assert_eq!(response["id"], 1);
+            assert_eq!(response["result"]["seams"].as_array().unwrap().len(), 1);
+
+            let response = client.call("check_point", json!({ "seam": 0, "w": 50.0 }));
+            let result = &response["result"];
+            assert_eq!(result["w"], 50.0);
+            assert!(result["status"].is_string());
+            assert_eq!(result["position"].as_array().unwrap().len(), 3);
+
+            let response = client.call("surfaces", Value::Null);
+            let surfaces: Vec<Surface> =
+                serde_json::from_value(response["result"].clone()).unwrap();
+            assert_eq!(surfaces, game_state().surfaces);
+        });
+    }
+
+    #[test]
+    fn error_codes() {
+        with_server(|server| {
+            let mut client = Client::connect(server);
+
+            let response = client.send("{not json");
+            assert_eq!(response["error"]["code"], PARSE_ERROR);
+            assert_eq!(response["id"], Value::Null);
+
+            let response = client.call("unknown", Value::Null);
+            assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+
+            let response = client.call("check_point", json!({ "seam": 0 }));
+            assert_eq!(response["error"]["code"], INVALID_PARAMS);
+
+            let response = client.call("seam_progress", json!({ "seam": 5 }));
+            assert_eq!(response["error"]["code"], INVALID_PARAMS);
+        });
+    }
+
+    #[test]
+    fn subscribe() {
+        with_server(|server| {
+            let mut client = Client::connect(server);
+
+            let response = client.call("subscribe", Value::Null);
+            assert_eq!(response["result"], true);
+
+            let notification = client.receive();
+            assert_eq!(notification["method"], "progress");
+            assert_eq!(notification["params"]["seams"][0]["seam"], 0);
+        });
+    }
+
+    #[test]
+    fn no_snapshot_without_clients() {
+        let server = Server::start("127.0.0.1:0").unwrap();
+        let state = game_state();
+        let mut seam_processor = SeamProcessor::new();
+        seam_processor.update(&state);
+        server.update(&state, &seam_processor);
+
+        let snapshot = server.shared.snapshot.lock().unwrap();
+        assert_eq!(snapshot.version, 0);
+        assert!(snapshot.seams.is_empty());
+    }
+
+    #[test]
+    fn drop_closes_connections() {
+        let server = Server::start("127.0.0.1:0").unwrap();
+        let mut client = Client::connect(&server);
+        client.call("subscribe", Value::Null);
+
+        drop(server);
+
+        let mut line = String::new();
+        assert_eq!(client.reader.read_line(&mut line).unwrap(), 0);
+    }
+
+    #[test]
+    fn drop_with_stalled_subscriber() {
+        let server = Server::start("127.0.0.1:0").unwrap();
+        let mut client = Client::connect(&server);
+        client.call("subscribe", Value::Null);
+
+        // The client never reads, so the notifier eventually blocks writing to it
+        let large = "x".repeat(1 << 20);
+        for _ in 0..MAX_PENDING_NOTIFICATIONS {
+            server.notify(json!({ "jsonrpc": "2.0", "method": "test", "params": large }));
+        }
+
+        drop(server);
+    }
+}
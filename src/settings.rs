@@ -0,0 +1,129 @@
+use crate::{
+    export::{Compression, ExportFormat},
+    palette::DEFAULT_PALETTE,
+    seam::{PointFilter, PointStatusFilter},
+    server::DEFAULT_SERVER_ADDR,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    io::{self, BufWriter},
+    path::Path,
+};
+
+pub const SETTINGS_FILE: &str = "settings.json";
+
+/// User settings that are restored on the next launch.
+///
+/// Missing fields use their default values, so settings files from older versions still load.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// The canonicalized name of the last process that was connected to.
+    pub process_name: Option<String>,
+    pub base_address: Option<usize>,
+    pub game_version: Option<String>,
+    pub sync_to_game: bool,
+    /// The display name of the y filter. Custom filters are stored by name since their indices
+    /// can change when scripts are reloaded.
+    pub point_filter_name: String,
+    /// The name of the selected palette.
+    pub palette: String,
+    pub show_legend: bool,
+    /// The radius of the wall hitboxes drawn in the world view, or 0 to hide them.
+    pub wall_hitbox_radius: f32,
+    pub show_mario: bool,
+    /// The number of frames of Mario's trail that are shown.
+    pub mario_trail_frames: usize,
+    /// The address that the query server listens on.
+    pub server_addr: String,
+    pub export: ExportDefaults,
+    /// The fraction of the window height used by the world view while the seam view is open.
+    pub game_view_split: f32,
+    pub window_size: Option<[u32; 2]>,
+    /// The positions and sizes of floating windows, in imgui's ini format.
+    pub window_layout: String,
+    /// Set when the settings are reset, so that the current window layout isn't saved.
+    #[serde(skip)]
+    pub reset_layout: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            process_name: None,
+            base_address: None,
+            game_version: None,
+            sync_to_game: false,
+            point_filter_name: PointFilter::default().to_string(),
+            palette: DEFAULT_PALETTE.to_owned(),
+            show_legend: false,
+            wall_hitbox_radius: 0.0,
+            show_mario: true,
+            mario_trail_frames: 60,
+            server_addr: DEFAULT_SERVER_ADDR.to_owned(),
+            export: ExportDefaults::default(),
+            game_view_split: 0.5,
+            window_size: None,
+            window_layout: String::new(),
+            reset_layout: false,
+        }
+    }
+}
+
+/// The options used for the last seam export.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExportDefaults {
+    pub filename: String,
+    pub status_filter: PointStatusFilter,
+    pub format: ExportFormat,
+    pub compression: Compression,
+    pub include_small_w: bool,
+}
+
+impl Default for ExportDefaults {
+    fn default() -> Self {
+        Self {
+            filename: "seam.csv".to_owned(),
+            status_filter: PointStatusFilter::GapsAndOverlaps,
+            format: ExportFormat::Points,
+            compression: Compression::None,
+            include_small_w: false,
+        }
+    }
+}
+
+impl Settings {
+    /// Load settings from a file, or return the default settings if the file doesn't exist.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(text) => Ok(serde_json::from_str(&text)?),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => Err(error),
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        // Rename a temporary file into place, so that a crash while saving keeps the old settings
+        let path = path.as_ref();
+        let mut temp_path = path.as_os_str().to_owned();
+        temp_path.push(".tmp");
+
+        let mut writer = BufWriter::new(fs::File::create(&temp_path)?);
+        serde_json::to_writer_pretty(&mut writer, self)?;
+        writer
+            .into_inner()
+            .map_err(io::IntoInnerError::into_error)?
+            .sync_all()?;
+        fs::rename(&temp_path, path)
+    }
+
+    /// Reset everything to the defaults. The window layout is reset on the next launch.
+    pub fn reset(&mut self) {
+        *self = Self {
+            reset_layout: true,
+            ..Self::default()
+        };
+    }
+}
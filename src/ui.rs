@@ -1,32 +1,90 @@
-use std::{fs, io::BufWriter, thread};
+use std::{
+    f32::consts::PI,
+    io, iter, mem,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread::{self, JoinHandle},
+};
 
 use crate::{
+    bookmarks::{BOOKMARKS_FILE, Bookmark, Bookmarks},
+    diagnostics::create_diagnostic_bundle,
     edge::{Edge, Orientation, ProjectedPoint, ProjectionAxis},
-    float_range::RangeF32,
-    game_state::GameState,
-    geo::{Point3f, point_f64_to_f32},
+    export::{
+        Compression, ExportFormat, ExportJob, ExportProgress, available_space,
+        estimate_export_size, export_to_file, format_size, load_checkpoint, read_points_csv,
+        resume_export,
+    },
+    float_range::{RangeF32, next_f32},
+    game_state::{GameState, read_surface_pool_bytes},
+    geo::{Point3f, Vector3f, direction_to_pitch_yaw, pitch_yaw_to_direction, point_f64_to_f32},
     graphics::{
-        Camera, GameViewScene, Scene, SeamViewCamera, SeamViewScene, Viewport,
-        seam_view_screen_to_world,
+        BirdsEyeCamera, Camera, GameViewScene, MarioInfo, OverlayPoint, RotateCamera, Scene,
+        SeamViewCamera, SeamViewScene, Viewport, seam_view_screen_to_world,
+    },
+    logging::{LOG_FILE, clear_recent_records, recent_records},
+    mesh_export::{MeshFormat, save_scene_mesh},
+    model::{
+        App, BIRDS_EYE_CAMERA_Y, BookmarkForm, CameraMode, ConnectedView, ConnectionMenu,
+        DEFAULT_BIRDS_EYE_SPAN, FreeFlyCamera, GapHistogram, MeshExportForm, OverlayLoad,
+        PointSearch, SeamExportForm, SeamOverlay, SeamViewState,
     },
-    model::{App, ConnectedView, ConnectionMenu, SeamExportForm, SeamViewState},
-    seam::PointFilter,
+    palette::Palette,
+    report::{build_area_report, save_area_report},
+    scripting::SCRIPT_DIR,
     seam::PointStatusFilter,
+    seam::{PointFilter, PointStatus, RangeStatus, Seam},
+    seam_list::{SeamListColumn, SeamListRow},
+    server::Server,
+    settings::ExportDefaults,
     util::get_visible_w_range,
     util::get_visible_w_range_for_seam,
     util::get_visible_y_range,
-    util::save_seam_to_csv,
     util::{
-        build_game_view_scene, canonicalize_process_name, find_hovered_seam, get_focused_seam_info,
-        get_mouse_ray, get_norm_mouse_pos, sync_to_game,
+        build_game_view_scene, canonicalize_process_name, count_points_in_buckets,
+        distance_to_seam, find_hovered_seam, find_nearest_seam_from_above, find_next_point,
+        find_surface_seams, get_birds_eye_mouse_ray, get_focused_seam_info, get_mouse_ray,
+        get_norm_mouse_pos, histogram_buckets, parse_f32, ray_surface_intersection, sync_to_game,
+        yaw_to_direction,
     },
 };
-use fs::File;
-use imgui::{Condition, MouseButton, Ui};
+use imgui::{Condition, Key, MouseButton, TabBarFlags, TabItemFlags, Ui};
 use itertools::Itertools;
+use log::Level;
 use nalgebra::{Point3, Vector3};
 use sysinfo::ProcessesToUpdate;
 
+const MAX_OVERLAY_POINTS: usize = 1_000_000;
+const MAX_OVERLAY_POINTS_SHOWN: usize = 5_000;
+/// The number of floats visible vertically after jumping to a gap or overlap.
+const POINT_SEARCH_SPAN_FLOATS: f64 = 32.0;
+const DEFAULT_ISOLATED_REGION_RADIUS: f32 = 1000.0;
+const GAME_VIEW_FOV_Y: f32 = 45.0;
+/// Radians per pixel when looking around with the free-fly camera.
+const MOUSE_LOOK_SPEED: f32 = 0.005;
+const MAX_FREE_FLY_PITCH: f32 = 1.5;
+/// Units per second.
+const FREE_FLY_SPEED: f32 = 2000.0;
+/// How much faster the camera moves while shift is held.
+const CAMERA_FAST_MULTIPLIER: f32 = 4.0;
+const BIRDS_EYE_ZOOM_STEP: f32 = 0.8;
+const MIN_BIRDS_EYE_SPAN: f32 = 10.0;
+const MAX_BIRDS_EYE_SPAN: f32 = 40_000.0;
+const BIRDS_EYE_SEAM_HOVER_PIXELS: f32 = 6.0;
+const SPLITTER_HEIGHT: f32 = 6.0;
+const HISTOGRAM_HEIGHT: f32 = 40.0;
+const MAX_MARIO_TRAIL_FRAMES: i32 = 3000;
+/// How close Mario has to be to a seam to be shown in the seam view.
+const MARIO_SEAM_VIEW_DISTANCE: f32 = 200.0;
+const NUM_HISTOGRAM_BUCKETS: usize = 200;
+/// The radii that Mario's wall collision checks use: 50 units at the lower check and 24 at the
+/// upper one.
+const WALL_HITBOX_PRESETS: [f32; 2] = [50.0, 24.0];
+/// The smallest fraction of the window that the world view or seam view can be resized to.
+const MIN_VIEW_SPLIT: f32 = 0.1;
+
 pub fn render_app(ui: &Ui, app: &mut App) -> Vec<Scene> {
     let style_token = ui.push_style_color(imgui::StyleColor::WindowBg, [0.0, 0.0, 0.0, 0.0]);
 
@@ -73,11 +131,18 @@ fn render_connection_menu(ui: &Ui, menu: &mut ConnectionMenu) -> Option<Connecte
                 .position(|process| process.pid().as_u32() == selected_pid)
         })
         .unwrap_or_else(|| {
+            let process_name = |process: &&sysinfo::Process| {
+                canonicalize_process_name(process.name().to_str().expect("utf-8"))
+            };
+            let last_process = processes
+                .iter()
+                .position(|process| Some(process_name(process)) == menu.settings.process_name);
             let known_process = processes.iter().position(|process| {
-                let name = canonicalize_process_name(process.name().to_str().expect("utf-8"));
-                menu.config.base_addresses.contains_key(name.as_str())
+                menu.config
+                    .base_addresses
+                    .contains_key(process_name(process).as_str())
             });
-            known_process.unwrap_or(0)
+            last_process.or(known_process).unwrap_or(0)
         });
 
     ui.text("Connect to emulator");
@@ -109,13 +174,16 @@ fn render_connection_menu(ui: &Ui, menu: &mut ConnectionMenu) -> Option<Connecte
     }
     if changed_pid {
         if let Some(selected_process) = selected_process {
-            if let Some(base_addr) = menu
-                .config
-                .base_addresses
-                .get(canonicalize_process_name(selected_process.name().to_str()?).as_str())
+            let name = canonicalize_process_name(selected_process.name().to_str()?);
+            let last_base_addr = menu
+                .settings
+                .base_address
+                .filter(|_| menu.settings.process_name.as_ref() == Some(&name));
+            if let Some(base_addr) =
+                last_base_addr.or_else(|| menu.config.base_addresses.get(name.as_str()).copied())
             {
-                menu.selected_base_addr = Some(*base_addr);
-                menu.base_addr_buffer = format!("{:#X}", *base_addr);
+                menu.selected_base_addr = Some(base_addr);
+                menu.base_addr_buffer = format!("{:#X}", base_addr);
                 menu.base_addr_buffer.reserve(32);
             }
         }
@@ -136,16 +204,30 @@ fn render_connection_menu(ui: &Ui, menu: &mut ConnectionMenu) -> Option<Connecte
     if let Some(pid) = menu.selected_pid {
         if let Some(base_addr) = menu.selected_base_addr {
             if ui.button("Connect") {
+                let game_version = &menu.config.game_versions[menu.selected_version_index];
+                menu.settings.process_name = selected_process.map(|process| {
+                    canonicalize_process_name(process.name().to_str().expect("utf-8"))
+                });
+                menu.settings.base_address = Some(base_addr);
+                menu.settings.game_version = Some(game_version.name.clone());
                 return Some(ConnectedView::new(
                     pid,
                     base_addr,
-                    menu.config.game_versions[menu.selected_version_index]
-                        .globals
-                        .clone(),
+                    game_version.globals.clone(),
+                    Palette::builtin()
+                        .into_iter()
+                        .chain(menu.config.palettes.iter().cloned())
+                        .collect(),
+                    menu.settings.clone(),
                 ));
             }
+            ui.same_line();
         }
     }
+    if ui.button("Reset settings") {
+        menu.settings.reset();
+        menu.selected_version_index = 0;
+    }
 
     None
 }
@@ -157,13 +239,19 @@ fn render_connected_view(ui: &Ui, view: &mut ConnectedView) -> Vec<Scene> {
 
     let state = GameState::read(&view.globals, &view.process);
     view.seam_processor.update(&state);
+    if let Some(server) = &view.server {
+        server.update(&state, &view.seam_processor);
+    }
+    view.scripts.update(&state, &view.seam_processor);
+    view.update_mario_trail(&state);
 
     let mut scenes = Vec::new();
+    let window_height = ui.window_size()[1];
     ui.child_window("game-view")
         .size([
             0.0,
-            if view.seam_view.is_some() {
-                ui.window_size()[1] / 2.0
+            if !view.seam_views.is_empty() {
+                window_height * view.settings.game_view_split
             } else {
                 0.0
             },
@@ -172,9 +260,21 @@ fn render_connected_view(ui: &Ui, view: &mut ConnectedView) -> Vec<Scene> {
             scenes.push(Scene::GameView(render_game_view(ui, view, &state)));
         });
 
-    if view.seam_view.is_some() {
+    if !view.seam_views.is_empty() {
+        // Drag the gap between the views to resize them
+        ui.invisible_button("##split", [ui.content_region_avail()[0], SPLITTER_HEIGHT]);
+        if ui.is_item_active() {
+            view.settings.game_view_split = (view.settings.game_view_split
+                + ui.io().mouse_delta[1] / window_height)
+                .clamp(MIN_VIEW_SPLIT, 1.0 - MIN_VIEW_SPLIT);
+        }
+
         ui.child_window("seam-info").build(|| {
-            scenes.push(Scene::SeamView(render_seam_view(ui, view)));
+            scenes.extend(
+                render_seam_views(ui, view, &state)
+                    .into_iter()
+                    .map(Scene::SeamView),
+            );
         });
     }
 
@@ -182,6 +282,38 @@ fn render_connected_view(ui: &Ui, view: &mut ConnectedView) -> Vec<Scene> {
         render_export_form(ui, view);
     }
 
+    if view.show_scripts {
+        render_scripts_window(ui, view);
+    }
+
+    if view.show_area_report {
+        render_area_report_window(ui, view, &state);
+    }
+
+    if view.mesh_export_form.is_some() {
+        render_mesh_export_window(ui, view);
+    }
+
+    if view.show_seam_list {
+        render_seam_list_window(ui, view);
+    }
+
+    if view.show_bookmarks {
+        render_bookmarks_window(ui, view);
+    }
+
+    if view.inspected_surface.is_some() {
+        render_surface_inspector(ui, view, &state);
+    }
+
+    if view.show_surface_visibility {
+        render_surface_visibility_window(ui, view, &state);
+    }
+
+    if view.show_log {
+        render_log_window(ui, view, &state);
+    }
+
     scenes
 }
 
@@ -192,25 +324,100 @@ fn render_game_view(ui: &Ui, view: &mut ConnectedView, state: &GameState) -> Gam
         width: ui.window_size()[0],
         height: ui.window_size()[1],
     };
-    let scene = build_game_view_scene(
+    let camera = get_game_view_camera(ui, view, state);
+    let mut scene = build_game_view_scene(
         viewport,
+        camera,
         state,
         &view.seam_processor,
         view.hovered_seam.clone(),
+        view.seam_view().map(|seam_view| seam_view.seam.clone()),
+        view.surface_visibility.hidden_indices(&state.surfaces),
     );
-    if let Camera::Rotate(camera) = &scene.camera {
-        let mouse_ray = get_mouse_ray(ui.io().mouse_pos, ui.window_pos(), ui.window_size(), camera);
-        view.hovered_seam = mouse_ray.and_then(|mouse_ray| {
-            find_hovered_seam(state, view.seam_processor.active_seams(), mouse_ray)
+    scene.palette = view.palette().clone();
+    scene.wall_hitbox_radius = view.settings.wall_hitbox_radius;
+    if view.settings.show_mario {
+        scene.mario = state.mario.map(|mario| MarioInfo {
+            pos: mario.pos(),
+            face_dir: yaw_to_direction(mario.face_yaw),
+        });
+        scene.mario_trail = view.mario_trail.iter().copied().collect();
+    }
+    view.scripts.apply_seam_colors(&mut scene.seams);
+    scene.bookmarks = view
+        .seam_processor
+        .active_seams()
+        .iter()
+        .flat_map(|seam| {
+            view.bookmarks
+                .for_seam(seam)
+                .iter()
+                .map(|bookmark| Point3f::from(seam.point_at(bookmark.w, bookmark.y)))
+        })
+        .collect();
+    if let Some(form) = &mut view.mesh_export_form
+        && form.requested
+    {
+        form.requested = false;
+        let filename = form.filename();
+        form.status = Some(match save_scene_mesh(&filename, form.format, &scene) {
+            Ok(()) => format!("Saved {}", filename),
+            Err(error) => {
+                log::error!("Failed to export {}: {}", filename, error);
+                format!("Failed to save {}: {}", filename, error)
+            }
         });
     }
+    let mouse_ray = match &scene.camera {
+        Camera::Rotate(camera) => {
+            get_mouse_ray(ui.io().mouse_pos, ui.window_pos(), ui.window_size(), camera)
+        }
+        Camera::BirdsEye(camera) => {
+            get_birds_eye_mouse_ray(ui.io().mouse_pos, ui.window_pos(), ui.window_size(), camera)
+        }
+    };
+    view.hovered_seam = mouse_ray.and_then(|mouse_ray| match &scene.camera {
+        Camera::Rotate(_) => find_hovered_seam(
+            state,
+            &scene.hidden_surfaces,
+            view.seam_processor.active_seams(),
+            mouse_ray,
+        ),
+        // Walls are seen edge on from above, so pick the nearest seam instead
+        Camera::BirdsEye(camera) => find_nearest_seam_from_above(
+            view.seam_processor.active_seams(),
+            mouse_ray.0.x,
+            mouse_ray.0.z,
+            BIRDS_EYE_SEAM_HOVER_PIXELS * camera.span_y / ui.window_size()[1],
+        ),
+    });
+
+    // Right dragging moves the camera, so only treat a right click without dragging as a click
+    if ui.is_mouse_released(MouseButton::Right)
+        && ui.mouse_drag_delta_with_button(MouseButton::Right) == [0.0, 0.0]
+        && !ui.is_any_item_hovered()
+    {
+        let clicked_surface = mouse_ray
+            .and_then(|mouse_ray| {
+                ray_surface_intersection(state, &scene.hidden_surfaces, mouse_ray)
+            })
+            .map(|(surface_index, _)| surface_index);
+        if ui.io().key_ctrl {
+            if let Some(surface_index) = clicked_surface {
+                view.surface_visibility.hide(&state.surfaces[surface_index]);
+            }
+        } else {
+            view.inspected_surface = clicked_surface;
+        }
+    }
+    scene.hovered_surface = view.inspected_surface;
 
     if let Some(hovered_seam) = &view.hovered_seam {
         if ui.is_mouse_clicked(MouseButton::Left)
             && !ui.is_any_item_hovered()
             && view.export_form.is_none()
         {
-            view.seam_view = Some(SeamViewState::new(hovered_seam.clone()));
+            view.open_seam_view(hovered_seam.clone(), ui.io().key_ctrl);
         }
     }
 
@@ -219,27 +426,372 @@ fn render_game_view(ui: &Ui, view: &mut ConnectedView, state: &GameState) -> Gam
         "remaining: {}",
         view.seam_processor.remaining_seams()
     ));
+    if let Some(mario) = &state.mario {
+        ui.text(format!(
+            "mario: ({:.3}, {:.3}, {:.3}) vel ({:.3}, {:.3}, {:.3})",
+            mario.pos[0], mario.pos[1], mario.pos[2], mario.vel[0], mario.vel[1], mario.vel[2]
+        ));
+    }
+
+    if ui.checkbox("sync", &mut view.sync_to_game) {
+        view.settings.sync_to_game = view.sync_to_game;
+    }
+
+    let all_camera_modes = CameraMode::all();
+    let mut camera_mode_index = all_camera_modes
+        .iter()
+        .position(|&mode| mode == view.camera_mode)
+        .unwrap();
+    ui.set_next_item_width(100.0);
+    if ui.combo(
+        "camera",
+        &mut camera_mode_index,
+        &all_camera_modes,
+        |mode| format!("{}", mode).into(),
+    ) {
+        set_camera_mode(view, state, all_camera_modes[camera_mode_index]);
+    }
+
+    let mut palette_index = view
+        .palettes
+        .iter()
+        .position(|palette| palette == view.palette())
+        .unwrap();
+    ui.set_next_item_width(100.0);
+    if ui.combo("colors", &mut palette_index, &view.palettes, |palette| {
+        palette.name.as_str().into()
+    }) {
+        view.settings.palette = view.palettes[palette_index].name.clone();
+    }
+    ui.same_line();
+    ui.checkbox("legend", &mut view.settings.show_legend);
+
+    ui.set_next_item_width(100.0);
+    if ui
+        .input_float("wall hitbox", &mut view.settings.wall_hitbox_radius)
+        .display_format("%.0f")
+        .build()
+    {
+        view.settings.wall_hitbox_radius = view.settings.wall_hitbox_radius.max(0.0);
+    }
+    for radius in WALL_HITBOX_PRESETS {
+        ui.same_line();
+        if ui.small_button(format!("{}", radius)) {
+            view.settings.wall_hitbox_radius = radius;
+        }
+    }
+    ui.same_line();
+    if ui.small_button("off") {
+        view.settings.wall_hitbox_radius = 0.0;
+    }
 
-    ui.checkbox("sync", &mut view.sync_to_game);
+    ui.checkbox("Mario", &mut view.settings.show_mario);
+    ui.same_line();
+    let mut trail_frames = view.settings.mario_trail_frames as i32;
+    ui.set_next_item_width(100.0);
+    if ui.input_int("trail frames", &mut trail_frames).build() {
+        view.settings.mario_trail_frames = trail_frames.clamp(0, MAX_MARIO_TRAIL_FRAMES) as usize;
+    }
+    ui.same_line();
+    if ui.small_button("Clear trail") {
+        view.mario_trail.clear();
+    }
 
     let all_filters = PointFilter::all();
     let mut filter_index = all_filters
         .iter()
-        .position(|filter| view.seam_processor.filter() == *filter)
+        .position(|filter| view.seam_processor.filter() == filter)
         .unwrap();
     ui.set_next_item_width(100.0);
     if ui.combo("##filter", &mut filter_index, &all_filters, |filter| {
         format!("{}", filter).into()
     }) {
-        view.seam_processor.set_filter(all_filters[filter_index]);
+        view.seam_processor
+            .set_filter(all_filters[filter_index].clone());
+        view.settings.point_filter_name = all_filters[filter_index].to_string();
+    }
+
+    let mut server_enabled = view.server.is_some();
+    if ui.checkbox("server", &mut server_enabled) {
+        view.server = None;
+        view.server_error = None;
+        if server_enabled {
+            match Server::start(&view.settings.server_addr) {
+                Ok(server) => view.server = Some(server),
+                Err(error) => {
+                    log::error!("Failed to start server: {}", error);
+                    view.server_error = Some(error.to_string());
+                }
+            }
+        }
+    }
+    ui.same_line();
+    if let Some(server) = &view.server {
+        ui.text(format!("{}", server.addr()));
+    } else {
+        ui.set_next_item_width(120.0);
+        ui.input_text("##server-addr", &mut view.settings.server_addr)
+            .build();
+        if let Some(error) = &view.server_error {
+            ui.same_line();
+            ui.text(error);
+        }
+    }
+
+    if ui.button("Scripts") {
+        view.show_scripts = !view.show_scripts;
+    }
+    ui.same_line();
+    if ui.button("Area report") {
+        view.show_area_report = !view.show_area_report;
+    }
+    ui.same_line();
+    if ui.button("Export mesh") {
+        view.mesh_export_form = match view.mesh_export_form {
+            Some(_) => None,
+            None => Some(MeshExportForm::new()),
+        };
+    }
+    ui.same_line();
+    if ui.button("Seams") {
+        view.show_seam_list = !view.show_seam_list;
+    }
+    ui.same_line();
+    if ui.button("Bookmarks") {
+        view.show_bookmarks = !view.show_bookmarks;
+    }
+    ui.same_line();
+    if ui.button("Surfaces") {
+        view.show_surface_visibility = !view.show_surface_visibility;
+    }
+    ui.same_line();
+    if ui.button("Log") {
+        view.show_log = !view.show_log;
+    }
+    ui.same_line();
+    if ui.button("Reset settings") {
+        let mut settings = view.settings.clone();
+        settings.reset();
+        view.apply_settings(settings);
+    }
+
+    if view.settings.show_legend {
+        let mut entries = view.palette().legend();
+        entries.push(("bookmark", view.palette().bookmark_color()));
+        if scene.mario.is_some() {
+            entries.push(("Mario", view.palette().mario_color()));
+        }
+        render_legend(ui, &entries);
     }
 
     scene
 }
 
-fn render_seam_view(ui: &Ui, view: &mut ConnectedView) -> SeamViewScene {
-    let seam_view = view.seam_view.as_mut().unwrap();
+/// Switch camera mode, starting the new camera from the game camera's current position.
+fn set_camera_mode(view: &mut ConnectedView, state: &GameState, mode: CameraMode) {
+    let pos = Point3f::from(state.lakitu_pos);
+    let focus = Point3f::from(state.lakitu_focus);
+    match mode {
+        CameraMode::Follow => {}
+        CameraMode::FreeFly => {
+            let (pitch, yaw) = direction_to_pitch_yaw(&(focus - pos));
+            view.free_fly_camera = FreeFlyCamera { pos, pitch, yaw };
+        }
+        CameraMode::BirdsEye => {
+            view.birds_eye_camera = BirdsEyeCamera {
+                pos: [focus.x, BIRDS_EYE_CAMERA_Y, focus.z],
+                span_y: DEFAULT_BIRDS_EYE_SPAN,
+            };
+        }
+    }
+    view.camera_mode = mode;
+}
+
+/// Move the free-fly or birds-eye camera using the mouse and keyboard, and return the camera
+/// to render with.
+fn get_game_view_camera(ui: &Ui, view: &mut ConnectedView, state: &GameState) -> Camera {
+    let norm_mouse_pos = get_norm_mouse_pos(ui.io().mouse_pos, ui.window_pos(), ui.window_size());
+    let mouse_in_view =
+        !ui.is_any_item_hovered() && norm_mouse_pos.0.abs() <= 1.0 && norm_mouse_pos.1.abs() <= 1.0;
+
+    if ui.is_mouse_clicked(MouseButton::Right) && mouse_in_view {
+        view.camera_dragging = true;
+    }
+    if !ui.is_mouse_down(MouseButton::Right) {
+        view.camera_dragging = false;
+    }
+    let mouse_delta = if view.camera_dragging {
+        ui.io().mouse_delta
+    } else {
+        [0.0, 0.0]
+    };
+
+    let keys_enabled = mouse_in_view && !ui.io().want_text_input;
+    let key_axis = |negative: Key, positive: Key| {
+        if !keys_enabled {
+            return 0.0;
+        }
+        (ui.is_key_down(positive) as i32 - ui.is_key_down(negative) as i32) as f32
+    };
+    let mut speed_multiplier = ui.io().delta_time;
+    if ui.io().key_shift {
+        speed_multiplier *= CAMERA_FAST_MULTIPLIER;
+    }
+
+    match view.camera_mode {
+        CameraMode::Follow => Camera::Rotate(RotateCamera {
+            pos: state.lakitu_pos,
+            target: state.lakitu_focus,
+            fov_y: GAME_VIEW_FOV_Y,
+        }),
+        CameraMode::FreeFly => {
+            let camera = &mut view.free_fly_camera;
+            camera.yaw -= mouse_delta[0] * MOUSE_LOOK_SPEED;
+            camera.pitch = (camera.pitch - mouse_delta[1] * MOUSE_LOOK_SPEED)
+                .clamp(-MAX_FREE_FLY_PITCH, MAX_FREE_FLY_PITCH);
+
+            let right_dir = pitch_yaw_to_direction(0.0, camera.yaw - PI / 2.0);
+            camera.pos += FREE_FLY_SPEED
+                * speed_multiplier
+                * (key_axis(Key::S, Key::W) * camera.dir()
+                    + key_axis(Key::A, Key::D) * right_dir
+                    + key_axis(Key::Q, Key::E) * Vector3f::y());
+
+            // The distance to the target only affects the near clipping plane
+            Camera::Rotate(RotateCamera {
+                pos: camera.pos.into(),
+                target: (camera.pos + 100.0 * camera.dir()).into(),
+                fov_y: GAME_VIEW_FOV_Y,
+            })
+        }
+        CameraMode::BirdsEye => {
+            let camera = &mut view.birds_eye_camera;
+            let window_size = ui.window_size();
+            let aspect = window_size[0] / window_size[1];
+
+            // Drag the level under the mouse. World x is screen up and world z is screen right
+            let units_per_pixel = camera.span_y / window_size[1];
+            camera.pos[0] += mouse_delta[1] * units_per_pixel;
+            camera.pos[2] -= mouse_delta[0] * units_per_pixel;
+
+            let pan_speed = camera.span_y * speed_multiplier;
+            camera.pos[0] += key_axis(Key::S, Key::W) * pan_speed;
+            camera.pos[2] += key_axis(Key::A, Key::D) * pan_speed;
+
+            if mouse_in_view {
+                // Zoom while keeping the point under the mouse in place
+                let span_y = (camera.span_y * BIRDS_EYE_ZOOM_STEP.powf(ui.io().mouse_wheel))
+                    .clamp(MIN_BIRDS_EYE_SPAN, MAX_BIRDS_EYE_SPAN);
+                camera.pos[0] += norm_mouse_pos.1 * (camera.span_y - span_y) / 2.0;
+                camera.pos[2] += norm_mouse_pos.0 * (camera.span_y - span_y) * aspect / 2.0;
+                camera.span_y = span_y;
+            }
+
+            Camera::BirdsEye(camera.clone())
+        }
+    }
+}
+
+/// Render the seam view tabs, and either the selected seam view or all of them side by side.
+fn render_seam_views(ui: &Ui, view: &mut ConnectedView, state: &GameState) -> Vec<SeamViewScene> {
+    ui.checkbox("side by side", &mut view.seam_views_side_by_side);
+    ui.same_line();
+    if ui.checkbox("lock cameras", &mut view.lock_seam_view_cameras) {
+        view.locked_seam_view_camera = view
+            .seam_view()
+            .and_then(|seam_view| seam_view.camera_lock());
+    }
+
+    let select_tab = mem::take(&mut view.select_seam_view_tab);
+    let mut closed = None;
+    if let Some(_tab_bar) =
+        ui.tab_bar_with_flags("##seam-views", TabBarFlags::FITTING_POLICY_SCROLL)
+    {
+        for (index, seam_view) in view.seam_views.iter().enumerate() {
+            let (endpoint1, endpoint2) = seam_view.seam.endpoints;
+            let label = format!(
+                "{} - {}##seam-view-{}",
+                SeamListRow::endpoint_text(endpoint1),
+                SeamListRow::endpoint_text(endpoint2),
+                seam_view.id,
+            );
+            let flags = if select_tab && index == view.active_seam_view {
+                TabItemFlags::SET_SELECTED
+            } else {
+                TabItemFlags::empty()
+            };
+            let mut opened = true;
+            // A newly selected tab only becomes visible on the next frame
+            if ui
+                .tab_item_with_flags(label, Some(&mut opened), flags)
+                .is_some()
+                && !select_tab
+            {
+                view.active_seam_view = index;
+            }
+            if !opened {
+                closed = Some(index);
+            }
+        }
+    }
+
+    let mut scenes = Vec::new();
+    if view.seam_views_side_by_side {
+        let count = view.seam_views.len();
+        let spacing = ui.clone_style().item_spacing[0];
+        let width = (ui.content_region_avail()[0] - spacing * (count - 1) as f32) / count as f32;
+        for index in 0..count {
+            if index > 0 {
+                ui.same_line();
+            }
+            ui.child_window(format!("seam-view-{}", view.seam_views[index].id))
+                .size([width, 0.0])
+                .build(|| {
+                    if ui.is_window_hovered() && ui.is_mouse_clicked(MouseButton::Left) {
+                        view.active_seam_view = index;
+                    }
+                    let (scene, close) = render_seam_view(ui, view, state, index);
+                    scenes.push(scene);
+                    if close {
+                        closed = Some(index);
+                    }
+                });
+        }
+    } else if view.active_seam_view < view.seam_views.len() {
+        let index = view.active_seam_view;
+        ui.child_window("seam-view").build(|| {
+            let (scene, close) = render_seam_view(ui, view, state, index);
+            scenes.push(scene);
+            if close {
+                closed = Some(index);
+            }
+        });
+    }
+
+    if let Some(index) = closed {
+        view.close_seam_view(index);
+    }
+    scenes
+}
+
+/// Render one seam view, and return its scene and whether it was closed.
+fn render_seam_view(
+    ui: &Ui,
+    view: &mut ConnectedView,
+    state: &GameState,
+    index: usize,
+) -> (SeamViewScene, bool) {
+    let is_active = index == view.active_seam_view;
+    let seam_view = &mut view.seam_views[index];
     let seam = seam_view.seam.clone();
+    let filter = seam_view.filter.clone();
+
+    if view.lock_seam_view_cameras
+        && let Some(lock) = view.locked_seam_view_camera
+    {
+        seam_view.set_camera_lock(lock);
+    }
+    let prev_camera = seam_view.camera_lock();
 
     let viewport = Viewport {
         x: ui.window_pos()[0],
@@ -252,6 +804,10 @@ fn render_seam_view(ui: &Ui, view: &mut ConnectedView) -> SeamViewScene {
     let screen_mouse_pos = Point3f::new(screen_mouse_pos.0, screen_mouse_pos.1, 0.0);
 
     let mut camera = get_seam_view_camera(seam_view, &viewport);
+    if let Some(point) = seam_view.pending_jump.take() {
+        jump_to_point(seam_view, point);
+        camera = get_seam_view_camera(seam_view, &viewport);
+    }
     let mut world_mouse_pos = seam_view_screen_to_world(&camera, &viewport, screen_mouse_pos);
 
     if ui.is_mouse_clicked(MouseButton::Left)
@@ -285,14 +841,32 @@ fn render_seam_view(ui: &Ui, view: &mut ConnectedView) -> SeamViewScene {
 
         camera = get_seam_view_camera(seam_view, &viewport);
         world_mouse_pos = seam_view_screen_to_world(&camera, &viewport, screen_mouse_pos);
+
+        let rounded_mouse = point_f64_to_f32(world_mouse_pos);
+        seam_view.hovered_point = Some(match seam.edge1.projection_axis {
+            ProjectionAxis::X => ProjectedPoint {
+                w: rounded_mouse.z,
+                y: rounded_mouse.y,
+            },
+            ProjectionAxis::Z => ProjectedPoint {
+                w: rounded_mouse.x,
+                y: rounded_mouse.y,
+            },
+        });
     }
 
+    let seam_view_id = seam_view.id;
+
     let segment_length = camera.span_y as f32 / 100.0;
     let visible_w_range = get_visible_w_range_for_seam(&camera, &viewport, &seam);
 
-    let progress =
-        view.seam_processor
-            .focused_seam_progress(&seam, visible_w_range, segment_length);
+    let progress = view.seam_processor.focused_seam_progress(
+        seam_view_id,
+        &seam,
+        visible_w_range,
+        segment_length,
+        filter.clone(),
+    );
 
     let mut vertical_grid_lines = Vec::new();
     let mut horizontal_grid_lines = Vec::new();
@@ -313,13 +887,82 @@ fn render_seam_view(ui: &Ui, view: &mut ConnectedView) -> SeamViewScene {
         }
     }
 
-    let scene = SeamViewScene {
+    let mut overlay_points = Vec::new();
+    let mut overlay_too_dense = false;
+    if let Some(overlay) = &seam_view.overlay {
+        let start = overlay
+            .points
+            .partition_point(|point| point.w < w_range.start);
+        let end = overlay
+            .points
+            .partition_point(|point| point.w < w_range.end);
+        let visible_points = &overlay.points[start..end];
+
+        if visible_points.len() <= MAX_OVERLAY_POINTS_SHOWN {
+            overlay_points = visible_points
+                .iter()
+                .map(|point| {
+                    let (y, status) = seam.check_point(point.w, &filter);
+                    let pos = match seam.edge1.projection_axis {
+                        ProjectionAxis::X => Point3f::new(0.0, point.y, point.w),
+                        ProjectionAxis::Z => Point3f::new(point.w, point.y, 0.0),
+                    };
+                    OverlayPoint {
+                        pos,
+                        differs: status != point.status || y.to_bits() != point.y.to_bits(),
+                    }
+                })
+                .collect();
+        } else {
+            overlay_too_dense = true;
+        }
+    }
+
+    let bookmarks = view
+        .bookmarks
+        .for_seam(&seam)
+        .iter()
+        .map(|bookmark| match seam.edge1.projection_axis {
+            ProjectionAxis::X => Point3f::new(0.0, bookmark.y, bookmark.w),
+            ProjectionAxis::Z => Point3f::new(bookmark.w, bookmark.y, 0.0),
+        })
+        .collect();
+
+    let mut scene = SeamViewScene {
         viewport,
         camera,
         seam: get_focused_seam_info(&seam, &progress),
         vertical_grid_lines,
         horizontal_grid_lines,
+        overlay_points,
+        bookmarks,
+        palette: view.palette().clone(),
+        mario: None,
+        mario_trail: Vec::new(),
     };
+    if view.settings.show_mario {
+        let is_near = |pos: &Point3f| distance_to_seam(&seam, *pos) <= MARIO_SEAM_VIEW_DISTANCE;
+        scene.mario = state
+            .mario
+            .map(|mario| mario.pos())
+            .filter(|pos| is_near(pos));
+        scene.mario_trail = view.mario_trail.iter().copied().filter(is_near).collect();
+    }
+
+    let seam_view = &view.seam_views[index];
+    if (!scene.vertical_grid_lines.is_empty() || !scene.horizontal_grid_lines.is_empty())
+        && seam_view.mouse_drag_start_pos.is_none()
+        && !ui.is_any_item_hovered()
+        && screen_mouse_pos.x.abs() <= 1.0
+        && screen_mouse_pos.y.abs() <= 1.0
+    {
+        let rounded_mouse = point_f64_to_f32(world_mouse_pos);
+        let point = ProjectedPoint::project(
+            [rounded_mouse.x, rounded_mouse.y, rounded_mouse.z],
+            seam.edge1.projection_axis,
+        );
+        render_point_tooltip(ui, &seam, point, &filter, view.palette());
+    }
 
     let close_seam_view = ui.button("Close");
 
@@ -329,12 +972,112 @@ fn render_seam_view(ui: &Ui, view: &mut ConnectedView) -> SeamViewScene {
             "Exporting ({:.1}%)",
             progress.complete as f32 / progress.total as f32 * 100.0,
         ));
+        ui.same_line();
+        if ui.small_button("Cancel") {
+            view.export_cancel.store(true, Ordering::Relaxed);
+        }
     } else if ui.button("Export") {
         view.export_form = Some(SeamExportForm::new(
             seam.clone(),
-            view.seam_processor.filter(),
+            filter,
+            &view.settings.export,
+        ));
+    }
+
+    let seam_view = &mut view.seam_views[index];
+    let all_filters = PointFilter::all();
+    let mut filter_index = all_filters
+        .iter()
+        .position(|filter| seam_view.filter == *filter)
+        .unwrap_or(0);
+    ui.same_line();
+    ui.set_next_item_width(100.0);
+    if ui.combo(
+        "##seam-view-filter",
+        &mut filter_index,
+        &all_filters,
+        |filter| format!("{}", filter).into(),
+    ) {
+        seam_view.filter = all_filters[filter_index].clone();
+    }
+
+    ui.same_line();
+    ui.set_next_item_width(150.0);
+    ui.input_text("##overlay-filename", &mut seam_view.overlay_filename_buffer)
+        .build();
+    ui.same_line();
+    let finished_load = seam_view
+        .overlay_load
+        .as_ref()
+        .and_then(|load| Some((load.filename.clone(), load.result.lock().unwrap().take()?)));
+    if let Some((filename, result)) = finished_load {
+        seam_view.overlay_load = None;
+        match result {
+            Ok((points, truncated)) => {
+                seam_view.overlay = Some(SeamOverlay {
+                    filename,
+                    points,
+                    truncated,
+                });
+                seam_view.overlay_error = None;
+            }
+            Err(error) => {
+                seam_view.overlay = None;
+                seam_view.overlay_error = Some(format!("Failed to load {}: {}", filename, error));
+            }
+        }
+    }
+
+    if let Some(load) = &seam_view.overlay_load {
+        match load.progress.lock().unwrap().as_ref() {
+            Some(progress) => ui.text(format!(
+                "Loading ({:.1}%)",
+                progress.complete as f32 / progress.total.max(1) as f32 * 100.0,
+            )),
+            None => ui.text("Loading..."),
+        }
+        ui.same_line();
+        if ui.small_button("Cancel##overlay") {
+            seam_view.overlay_load = None;
+        }
+    } else if ui.button("Load overlay") {
+        seam_view.overlay_load = Some(spawn_overlay_load(
+            seam_view.overlay_filename_buffer.trim().to_owned(),
+            seam.edge1.projection_axis,
         ));
     }
+    if seam_view.overlay.is_some() {
+        ui.same_line();
+        if ui.button("Clear overlay") {
+            seam_view.overlay = None;
+        }
+    }
+
+    if let Some(overlay) = &seam_view.overlay {
+        let num_differ = scene
+            .overlay_points
+            .iter()
+            .filter(|point| point.differs)
+            .count();
+        let mut info = format!(
+            "{}: {} points{}",
+            overlay.filename,
+            overlay.points.len(),
+            if overlay.truncated {
+                " (truncated)"
+            } else {
+                ""
+            },
+        );
+        if overlay_too_dense {
+            info += ", zoom in to compare";
+        } else {
+            info += &format!(", {} differ in view", num_differ);
+        }
+        ui.text(info);
+    } else if let Some(error) = &seam_view.overlay_error {
+        ui.text_colored([1.0, 0.3, 0.3, 1.0], error);
+    }
 
     ui.spacing();
 
@@ -358,49 +1101,622 @@ fn render_seam_view(ui: &Ui, view: &mut ConnectedView) -> SeamViewScene {
         }
     }
 
-    if close_seam_view {
-        view.seam_view = None;
+    render_seam_view_tools(ui, seam_view);
+    render_point_search(ui, seam_view, is_active);
+    render_bookmark_form(ui, seam_view, &mut view.bookmarks);
+    render_gap_histogram(ui, seam_view, visible_w_range, &scene.palette);
+
+    // A new seam view picks up the locked camera once it has been shown
+    if view.lock_seam_view_cameras
+        && prev_camera.is_some()
+        && seam_view.camera_lock() != prev_camera
+    {
+        view.locked_seam_view_camera = seam_view.camera_lock();
     }
-    scene
-}
 
-fn get_seam_view_camera(seam_view: &mut SeamViewState, viewport: &Viewport) -> SeamViewCamera {
-    let seam = &seam_view.seam;
+    let has_overlay = seam_view.overlay.is_some();
+    let has_mario = scene.mario.is_some() || !scene.mario_trail.is_empty();
+    if view.settings.show_legend {
+        let palette = view.palette();
+        let mut entries = palette.legend();
+        if has_overlay {
+            entries.push(("exported point", palette.exported_point_color(false)));
+            entries.push(("exported point differs", palette.exported_point_color(true)));
+        }
+        entries.push(("bookmark", palette.bookmark_color()));
+        if has_mario {
+            entries.push(("Mario", palette.mario_color()));
+        }
+        render_legend(ui, &entries);
+    }
 
-    let w_axis = match seam.edge1.projection_axis {
-        ProjectionAxis::X => Vector3::z(),
-        ProjectionAxis::Z => Vector3::x(),
-    };
-    let screen_right = match seam.edge1.orientation {
-        Orientation::Positive => -w_axis,
-        Orientation::Negative => w_axis,
+    (scene, close_seam_view)
+}
+
+/// Render the gap histogram for the whole seam, with the visible w range outlined. Clicking the
+/// histogram moves the camera to that w.
+fn render_gap_histogram(
+    ui: &Ui,
+    seam_view: &mut SeamViewState,
+    visible_w_range: RangeF32,
+    palette: &Palette,
+) {
+    let mut show_histogram = seam_view.histogram.is_some();
+    ui.checkbox("gap histogram", &mut show_histogram);
+    let outdated = seam_view
+        .histogram
+        .as_ref()
+        .is_some_and(|histogram| histogram.filter != seam_view.filter);
+    if !show_histogram {
+        seam_view.histogram = None;
+    } else if seam_view.histogram.is_none() || outdated {
+        seam_view.histogram = Some(spawn_gap_histogram(
+            &seam_view.seam,
+            seam_view.filter.clone(),
+        ));
+    }
+    let Some(histogram) = &seam_view.histogram else {
+        return;
     };
 
-    let initial_span_y = *seam_view.initial_span_y.get_or_insert_with(|| {
-        let w_range = seam.edge1.w_range();
-        let y_range = seam.edge1.y_range();
-        (y_range.end - y_range.start + 50.0)
-            .max((w_range.end - w_range.start + 50.0) * viewport.height / viewport.width)
-            as f64
-    });
-    let span_y = initial_span_y / 2.0f64.powf(seam_view.zoom);
+    let counts = histogram.counts.lock().unwrap().clone();
+    let num_counted = counts.iter().filter(|count| count.is_some()).count();
+    if num_counted < counts.len() {
+        ui.same_line();
+        ui.text(format!("counting ({}/{})", num_counted, counts.len()));
+    }
 
-    SeamViewCamera {
-        pos: seam_view.camera_pos,
-        span_y,
-        right_dir: screen_right,
+    let seam_w_range = seam_view.seam.w_range();
+    let w_span = seam_w_range.end - seam_w_range.start;
+    let width = ui.content_region_avail()[0];
+    let top_left = ui.cursor_screen_pos();
+    let bottom = top_left[1] + HISTOGRAM_HEIGHT;
+    let w_to_x = |w: f32| top_left[0] + (w - seam_w_range.start) / w_span * width;
+
+    ui.invisible_button("##gap-histogram", [width, HISTOGRAM_HEIGHT]);
+
+    let draw_list = ui.get_window_draw_list();
+    draw_list
+        .add_rect(
+            top_left,
+            [top_left[0] + width, bottom],
+            [0.0, 0.0, 0.0, 0.7],
+        )
+        .filled(true)
+        .build();
+
+    // Counts vary a lot between seams, so bar heights use a log scale
+    let max_count = counts
+        .iter()
+        .flatten()
+        .map(|(num_gaps, num_overlaps)| num_gaps + num_overlaps)
+        .max()
+        .unwrap_or_default();
+    let scale = |count: usize| (count as f32).ln_1p() / (max_count as f32).ln_1p();
+    let [gap_color, overlap_color] =
+        [PointStatus::Gap, PointStatus::Overlap].map(|status| palette.point_color(status));
+    for (bucket, count) in histogram.buckets.iter().zip(&counts) {
+        let x0 = w_to_x(bucket.start);
+        let x1 = w_to_x(bucket.end).max(x0 + 1.0);
+        match *count {
+            None => {
+                draw_list
+                    .add_rect(
+                        [x0, top_left[1]],
+                        [x1, bottom],
+                        palette.segment_color(RangeStatus::Unchecked),
+                    )
+                    .filled(true)
+                    .build();
+            }
+            Some((num_gaps, num_overlaps)) if num_gaps + num_overlaps > 0 => {
+                let total = num_gaps + num_overlaps;
+                let height = scale(total) * HISTOGRAM_HEIGHT;
+                let gap_top = bottom - height * num_gaps as f32 / total as f32;
+                draw_list
+                    .add_rect([x0, gap_top], [x1, bottom], gap_color)
+                    .filled(true)
+                    .build();
+                draw_list
+                    .add_rect([x0, bottom - height], [x1, gap_top], overlap_color)
+                    .filled(true)
+                    .build();
+            }
+            Some(_) => {}
+        }
+    }
+
+    let visible_range = visible_w_range.intersect(&seam_w_range);
+    if !visible_range.is_empty() {
+        draw_list
+            .add_rect(
+                [w_to_x(visible_range.start), top_left[1]],
+                [
+                    w_to_x(visible_range.end).max(w_to_x(visible_range.start) + 2.0),
+                    bottom,
+                ],
+                [1.0, 1.0, 1.0, 1.0],
+            )
+            .build();
+    }
+
+    if ui.is_item_hovered() {
+        let mouse_w = seam_w_range.start + (ui.io().mouse_pos[0] - top_left[0]) / width * w_span;
+        if let Some(index) = histogram
+            .buckets
+            .iter()
+            .position(|bucket| mouse_w >= bucket.start && mouse_w < bucket.end)
+        {
+            let bucket = histogram.buckets[index];
+            ui.tooltip_text(match counts[index] {
+                Some((num_gaps, num_overlaps)) => format!(
+                    "w = {} to {}\n{} gaps, {} overlaps",
+                    bucket.start, bucket.end, num_gaps, num_overlaps
+                ),
+                None => format!("w = {} to {}\ncounting...", bucket.start, bucket.end),
+            });
+        }
+
+        if ui.is_item_clicked() {
+            let w = mouse_w.clamp(seam_w_range.start, seam_w_range.end);
+            let y = seam_view.seam.edge1.approx_y(w);
+            match seam_view.seam.edge1.projection_axis {
+                ProjectionAxis::X => seam_view.camera_pos.z = w as f64,
+                ProjectionAxis::Z => seam_view.camera_pos.x = w as f64,
+            }
+            seam_view.camera_pos.y = y as f64;
+        }
     }
 }
 
-fn render_export_form(ui: &Ui, view: &mut ConnectedView) {
-    let form = view.export_form.as_mut().unwrap();
-    let progress_cell = view.export_progress.clone();
+fn spawn_overlay_load(filename: String, projection_axis: ProjectionAxis) -> OverlayLoad {
+    let progress = Arc::new(Mutex::new(None));
+    let result = Arc::new(Mutex::new(None));
+    let cancel = Arc::new(AtomicBool::new(false));
+
+    let filename2 = filename.clone();
+    let progress2 = Arc::clone(&progress);
+    let result2 = Arc::clone(&result);
+    let cancel2 = Arc::clone(&cancel);
+    thread::spawn(move || {
+        let points = read_points_csv(
+            &filename2,
+            |progress| {
+                if let Ok(mut progress2) = progress2.try_lock() {
+                    *progress2 = Some(progress);
+                }
+            },
+            &cancel2,
+            projection_axis,
+            MAX_OVERLAY_POINTS,
+        );
+        if !cancel2.load(Ordering::Relaxed) {
+            *result2.lock().unwrap() = Some(points);
+        }
+    });
 
-    let style_token = ui.push_style_color(imgui::StyleColor::WindowBg, [0.06, 0.06, 0.06, 0.94]);
+    OverlayLoad {
+        filename,
+        progress,
+        result,
+        cancel,
+    }
+}
 
-    let mut opened = true;
-    let mut begun = false;
-    ui.window("Export seam data")
+fn spawn_gap_histogram(seam: &Seam, filter: PointFilter) -> GapHistogram {
+    let buckets = histogram_buckets(seam.w_range(), NUM_HISTOGRAM_BUCKETS);
+    let counts = Arc::new(Mutex::new(vec![None; buckets.len()]));
+    let cancel = Arc::new(AtomicBool::new(false));
+
+    let seam = seam.clone();
+    let buckets2 = buckets.clone();
+    let counts2 = Arc::clone(&counts);
+    let cancel2 = Arc::clone(&cancel);
+    let filter2 = filter.clone();
+    thread::spawn(move || {
+        count_points_in_buckets(&seam, &buckets2, &filter2, &cancel2, |index, count| {
+            counts2.lock().unwrap()[index] = Some(count);
+        });
+    });
+
+    GapHistogram {
+        filter,
+        buckets,
+        counts,
+        cancel,
+    }
+}
+
+/// Draw a legend in the bottom left corner of the current window.
+fn render_legend(ui: &Ui, entries: &[(&str, [f32; 4])]) {
+    let padding = 8.0;
+    let line_height = ui.text_line_height_with_spacing();
+    let swatch_size = ui.text_line_height();
+    let width = entries
+        .iter()
+        .map(|(label, _)| ui.calc_text_size(label)[0])
+        .fold(0.0, f32::max)
+        + swatch_size
+        + 3.0 * padding;
+    let height = entries.len() as f32 * line_height + padding;
+
+    let window_pos = ui.window_pos();
+    let bottom = window_pos[1] + ui.window_size()[1];
+    let top_left = [window_pos[0] + padding, bottom - padding - height];
+
+    let draw_list = ui.get_window_draw_list();
+    draw_list
+        .add_rect(
+            top_left,
+            [top_left[0] + width, top_left[1] + height],
+            [0.0, 0.0, 0.0, 0.7],
+        )
+        .filled(true)
+        .build();
+    for (i, (label, color)) in entries.iter().enumerate() {
+        let pos = [
+            top_left[0] + padding,
+            top_left[1] + padding / 2.0 + i as f32 * line_height,
+        ];
+        draw_list
+            .add_rect(pos, [pos[0] + swatch_size, pos[1] + swatch_size], *color)
+            .filled(true)
+            .build();
+        draw_list.add_text(
+            [pos[0] + swatch_size + padding, pos[1]],
+            [1.0, 1.0, 1.0, 1.0],
+            label,
+        );
+    }
+}
+
+/// Show how each edge classifies the float under the mouse.
+fn render_point_tooltip(
+    ui: &Ui,
+    seam: &Seam,
+    point: ProjectedPoint<f32>,
+    filter: &PointFilter,
+    palette: &Palette,
+) {
+    ui.tooltip(|| {
+        ui.text(format!("w = {}, y = {}", point.w, point.y));
+        ui.text(format!(
+            "    {:#08X}, {:#08X}",
+            point.w.to_bits(),
+            point.y.to_bits()
+        ));
+
+        let in1 = seam.edge1.accepts_projected(point);
+        let in2 = seam.edge2.accepts_projected(point);
+        for (name, edge, accepts) in [("edge 1", &seam.edge1, in1), ("edge 2", &seam.edge2, in2)] {
+            ui.text(format!(
+                "{}: r = {}, {}",
+                name,
+                edge.cross_product(point),
+                if accepts { "accepts" } else { "rejects" }
+            ));
+        }
+
+        let status = match (in1, in2) {
+            (true, true) => PointStatus::Overlap,
+            (false, false) => PointStatus::Gap,
+            _ => PointStatus::None,
+        };
+        ui.text_colored(palette.point_color(status), status.to_string());
+        if status != PointStatus::None && !filter.matches(point) {
+            ui.same_line();
+            ui.text(format!("(hidden by {} filter)", filter));
+        }
+    });
+}
+
+fn render_seam_view_tools(ui: &Ui, seam_view: &mut SeamViewState) {
+    let seam = seam_view.seam.clone();
+
+    ui.spacing();
+    ui.text("Go to w:");
+    ui.same_line();
+    ui.set_next_item_width(100.0);
+    ui.input_text("##jump-w", &mut seam_view.jump_w_buffer)
+        .build();
+    ui.same_line();
+    ui.text("y:");
+    ui.same_line();
+    ui.set_next_item_width(100.0);
+    ui.input_text("##jump-y", &mut seam_view.jump_y_buffer)
+        .build();
+    ui.same_line();
+    ui.text("zoom:");
+    ui.same_line();
+    ui.set_next_item_width(60.0);
+    ui.input_text("##jump-zoom", &mut seam_view.jump_zoom_buffer)
+        .hint("current")
+        .build();
+    ui.same_line();
+    if ui.button("Go") {
+        let w = parse_f32(&seam_view.jump_w_buffer);
+        let y = parse_f32(&seam_view.jump_y_buffer);
+        let zoom_text = seam_view.jump_zoom_buffer.trim();
+        let zoom = if zoom_text.is_empty() {
+            Some(None)
+        } else {
+            zoom_text.parse::<f64>().ok().map(Some)
+        };
+        match (w, y, zoom) {
+            (Some(w), Some(y), Some(zoom)) => {
+                jump_to_point(seam_view, ProjectedPoint { w, y });
+                if let Some(zoom) = zoom {
+                    seam_view.zoom = zoom;
+                }
+                seam_view.jump_error = None;
+            }
+            (Some(_), Some(_), None) => {
+                seam_view.jump_error = Some(
+                    "Enter zoom as a decimal, or leave it empty to keep the current zoom"
+                        .to_owned(),
+                );
+            }
+            _ => {
+                seam_view.jump_error =
+                    Some("Enter w and y as decimals or hex bits (e.g. 0x44800000)".to_owned());
+            }
+        }
+    }
+    if let Some(error) = &seam_view.jump_error {
+        ui.text_colored([1.0, 0.3, 0.3, 1.0], error);
+    }
+
+    if let Some(point) = seam_view.hovered_point {
+        let [x, y, z] = seam.point_at(point.w, point.y);
+
+        ui.text("Copy:");
+        ui.same_line();
+        if ui.small_button("decimal") {
+            ui.set_clipboard_text(format!("w = {}, y = {}", point.w, point.y));
+        }
+        ui.same_line();
+        if ui.small_button("hex") {
+            ui.set_clipboard_text(format!(
+                "w = {:#010X}, y = {:#010X}",
+                point.w.to_bits(),
+                point.y.to_bits()
+            ));
+        }
+        ui.same_line();
+        if ui.small_button("xyz") {
+            ui.set_clipboard_text(format!("x = {}, y = {}, z = {}", x, y, z));
+        }
+    }
+}
+
+/// Render the next/previous gap buttons. The keyboard shortcuts only apply to the active seam
+/// view.
+fn render_point_search(ui: &Ui, seam_view: &mut SeamViewState, is_active: bool) {
+    let finished_search = seam_view
+        .point_search
+        .as_ref()
+        .and_then(|search| Some((search.forward, search.result.lock().unwrap().take()?)));
+    if let Some((forward, result)) = finished_search {
+        seam_view.point_search = None;
+        match result {
+            Some((point, status)) => {
+                jump_to_point(seam_view, point);
+                seam_view.point_search_message = Some(format!(
+                    "Found {} at w = {}, y = {}",
+                    status, point.w, point.y
+                ));
+            }
+            None => {
+                seam_view.point_search_message = Some(format!(
+                    "No {} gaps or overlaps",
+                    if forward { "later" } else { "earlier" }
+                ));
+            }
+        }
+    }
+
+    let mut search_direction = None;
+    if ui.small_button("< Prev gap") {
+        search_direction = Some(false);
+    }
+    ui.same_line();
+    if ui.small_button("Next gap >") {
+        search_direction = Some(true);
+    }
+    if is_active && !ui.io().want_text_input {
+        if ui.is_key_pressed(imgui::Key::P) {
+            search_direction = Some(false);
+        }
+        if ui.is_key_pressed(imgui::Key::N) {
+            search_direction = Some(true);
+        }
+    }
+
+    ui.same_line();
+    if seam_view.point_search.is_some() {
+        ui.text("Searching...");
+        ui.same_line();
+        if ui.small_button("Cancel##point-search") {
+            seam_view.point_search = None;
+            seam_view.point_search_message = None;
+        }
+    } else if let Some(message) = &seam_view.point_search_message {
+        ui.text(message);
+    }
+
+    if let Some(forward) = search_direction {
+        let seam = seam_view.seam.clone();
+        let filter = seam_view.filter.clone();
+        let from_w = match seam.edge1.projection_axis {
+            ProjectionAxis::X => seam_view.camera_pos.z,
+            ProjectionAxis::Z => seam_view.camera_pos.x,
+        } as f32;
+
+        let result = Arc::new(Mutex::new(None));
+        let cancel = Arc::new(AtomicBool::new(false));
+        let result2 = Arc::clone(&result);
+        let cancel2 = Arc::clone(&cancel);
+        thread::spawn(move || {
+            let point = find_next_point(&seam, from_w, forward, &filter, &cancel2);
+            if !cancel2.load(Ordering::Relaxed) {
+                *result2.lock().unwrap() = Some(point);
+            }
+        });
+
+        seam_view.point_search = Some(PointSearch {
+            forward,
+            result,
+            cancel,
+        });
+        seam_view.point_search_message = None;
+    }
+}
+
+fn render_bookmark_form(ui: &Ui, seam_view: &mut SeamViewState, bookmarks: &mut Bookmarks) {
+    let Some(form) = &mut seam_view.bookmark_form else {
+        if ui.small_button("Bookmark") {
+            let center = match seam_view.seam.edge1.projection_axis {
+                ProjectionAxis::X => ProjectedPoint {
+                    w: seam_view.camera_pos.z as f32,
+                    y: seam_view.camera_pos.y as f32,
+                },
+                ProjectionAxis::Z => ProjectedPoint {
+                    w: seam_view.camera_pos.x as f32,
+                    y: seam_view.camera_pos.y as f32,
+                },
+            };
+            seam_view.bookmark_form = Some(BookmarkForm::new(center));
+        }
+        return;
+    };
+
+    ui.text("Name:");
+    ui.same_line();
+    ui.set_next_item_width(120.0);
+    ui.input_text("##bookmark-name", &mut form.name).build();
+    ui.same_line();
+    ui.text("w:");
+    ui.same_line();
+    ui.set_next_item_width(100.0);
+    ui.input_text("##bookmark-w", &mut form.w_buffer).build();
+    ui.same_line();
+    ui.text("y:");
+    ui.same_line();
+    ui.set_next_item_width(100.0);
+    ui.input_text("##bookmark-y", &mut form.y_buffer).build();
+
+    ui.text("Comment:");
+    ui.same_line();
+    ui.set_next_item_width(300.0);
+    ui.input_text("##bookmark-comment", &mut form.comment)
+        .build();
+    ui.same_line();
+    let mut close_form = ui.small_button("Cancel##bookmark");
+    ui.same_line();
+    if ui.small_button("Save##bookmark") {
+        let w = parse_f32(&form.w_buffer);
+        let y = parse_f32(&form.y_buffer);
+        match (w, y) {
+            (Some(w), Some(y)) if !form.name.trim().is_empty() => {
+                // Only keep the bookmark if it was saved, so that saving again doesn't add it twice
+                let mut updated = bookmarks.clone();
+                updated.add(
+                    &seam_view.seam,
+                    Bookmark {
+                        name: form.name.trim().to_owned(),
+                        comment: form.comment.trim().to_owned(),
+                        w,
+                        y,
+                    },
+                );
+                form.error = save_bookmarks(&updated);
+                if form.error.is_none() {
+                    *bookmarks = updated;
+                    close_form = true;
+                }
+            }
+            _ => {
+                form.error = Some("Enter a name and valid w and y values".to_owned());
+            }
+        }
+    }
+    if let Some(error) = &form.error {
+        ui.text_colored([1.0, 0.3, 0.3, 1.0], error);
+    }
+
+    if close_form {
+        seam_view.bookmark_form = None;
+    }
+}
+
+fn save_bookmarks(bookmarks: &Bookmarks) -> Option<String> {
+    match bookmarks.save(BOOKMARKS_FILE) {
+        Ok(()) => None,
+        Err(error) => {
+            log::error!("Failed to save {}: {}", BOOKMARKS_FILE, error);
+            Some(format!("Failed to save {}: {}", BOOKMARKS_FILE, error))
+        }
+    }
+}
+
+/// Center the seam view on a point, zooming in far enough to see individual floats.
+fn jump_to_point(seam_view: &mut SeamViewState, point: ProjectedPoint<f32>) {
+    match seam_view.seam.edge1.projection_axis {
+        ProjectionAxis::X => {
+            seam_view.camera_pos.y = point.y as f64;
+            seam_view.camera_pos.z = point.w as f64;
+        }
+        ProjectionAxis::Z => {
+            seam_view.camera_pos.x = point.w as f64;
+            seam_view.camera_pos.y = point.y as f64;
+        }
+    }
+
+    if let Some(initial_span_y) = seam_view.initial_span_y {
+        let ulp = |x: f32| (next_f32(x.abs()) - x.abs()) as f64;
+        let span_y = POINT_SEARCH_SPAN_FLOATS * ulp(point.w).max(ulp(point.y));
+        let zoom = (initial_span_y / span_y).log2();
+        seam_view.zoom = seam_view.zoom.max(zoom);
+    }
+}
+
+fn get_seam_view_camera(seam_view: &mut SeamViewState, viewport: &Viewport) -> SeamViewCamera {
+    let seam = &seam_view.seam;
+
+    let w_axis = match seam.edge1.projection_axis {
+        ProjectionAxis::X => Vector3::z(),
+        ProjectionAxis::Z => Vector3::x(),
+    };
+    let screen_right = match seam.edge1.orientation {
+        Orientation::Positive => -w_axis,
+        Orientation::Negative => w_axis,
+    };
+
+    let initial_span_y = *seam_view.initial_span_y.get_or_insert_with(|| {
+        let w_range = seam.edge1.w_range();
+        let y_range = seam.edge1.y_range();
+        (y_range.end - y_range.start + 50.0)
+            .max((w_range.end - w_range.start + 50.0) * viewport.height / viewport.width)
+            as f64
+    });
+    let span_y = initial_span_y / 2.0f64.powf(seam_view.zoom);
+
+    SeamViewCamera {
+        pos: seam_view.camera_pos,
+        span_y,
+        right_dir: screen_right,
+    }
+}
+
+fn render_export_form(ui: &Ui, view: &mut ConnectedView) {
+    let form = view.export_form.as_mut().unwrap();
+    let progress_cell = view.export_progress.clone();
+    let cancel = view.export_cancel.clone();
+
+    let style_token = ui.push_style_color(imgui::StyleColor::WindowBg, [0.06, 0.06, 0.06, 0.94]);
+
+    let mut opened = true;
+    ui.window("Export seam data")
         .size([500.0, 300.0], Condition::Appearing)
         .opened(&mut opened)
         .build(|| {
@@ -443,7 +1759,75 @@ fn render_export_form(ui: &Ui, view: &mut ConnectedView) {
                     form.filename = None;
                 } else {
                     form.filename = Some(filename.to_owned());
+                    form.available_space = available_space(filename);
+                    form.checkpoint = load_checkpoint(filename);
                 }
+                form.resume_error = None;
+            }
+
+            if form
+                .export_thread
+                .as_ref()
+                .is_some_and(|thread| thread.is_finished())
+            {
+                form.export_thread = None;
+                if let Some(filename) = &form.filename {
+                    form.available_space = available_space(filename);
+                    form.checkpoint = load_checkpoint(filename);
+                }
+            }
+            let exporting = form.export_thread.is_some();
+
+            if exporting {
+                match progress_cell.lock().unwrap().as_ref() {
+                    Some(progress) => ui.text(format!(
+                        "Exporting ({:.1}%)",
+                        progress.complete as f32 / progress.total as f32 * 100.0,
+                    )),
+                    None => ui.text("Exporting..."),
+                }
+                ui.same_line();
+                if ui.button("Cancel##export") {
+                    cancel.store(true, Ordering::Relaxed);
+                }
+            } else if let (Some(checkpoint), Some(filename)) = (&form.checkpoint, &form.filename) {
+                let progress = checkpoint.progress();
+                ui.text(format!(
+                    "An interrupted export to this file was found ({:.1}%)",
+                    progress.complete as f32 / progress.total as f32 * 100.0,
+                ));
+                ui.same_line();
+                if ui.button("Resume export") {
+                    // The free space may have changed since the form was opened
+                    form.available_space = available_space(filename);
+                    let job = &checkpoint.job;
+                    let remaining_bytes = estimate_export_size(&job.seam, job.format, &job.options)
+                        .bytes
+                        .map(|bytes| {
+                            let remaining = (progress.total - progress.complete) as f64;
+                            (bytes as f64 * remaining / progress.total as f64) as u64
+                        });
+                    if fits_on_disk(remaining_bytes, form.available_space) {
+                        form.resume_error = None;
+                        let filename = filename.clone();
+                        form.export_thread = Some(spawn_export(
+                            &progress_cell,
+                            &cancel,
+                            filename.clone(),
+                            move |set_progress, cancel| {
+                                resume_export(&filename, set_progress, cancel)
+                            },
+                        ));
+                    } else {
+                        form.resume_error = Some(format!(
+                            "Not enough disk space to resume ({} free)",
+                            format_size(form.available_space.unwrap_or(0)),
+                        ));
+                    }
+                }
+            }
+            if let Some(error) = &form.resume_error {
+                ui.text_colored([1.0, 0.3, 0.3, 1.0], error);
             }
 
             ui.spacing();
@@ -482,7 +1866,7 @@ fn render_export_form(ui: &Ui, view: &mut ConnectedView) {
                 &all_filters,
                 |filter| format!("{}", filter).into(),
             ) {
-                form.point_filter = all_filters[filter_index];
+                form.point_filter = all_filters[filter_index].clone();
             }
 
             ui.spacing();
@@ -501,43 +1885,858 @@ fn render_export_form(ui: &Ui, view: &mut ConnectedView) {
                 form.status_filter = all_filters[filter_index];
             }
 
-            if let (Some(min_w), Some(max_w), Some(filename)) =
-                (form.min_w, form.max_w, form.filename.as_ref())
-            {
+            ui.spacing();
+            let all_formats = ExportFormat::all();
+            let mut format_index = all_formats
+                .iter()
+                .position(|format| form.format == *format)
+                .unwrap();
+            ui.set_next_item_width(150.0);
+            if ui.combo("##format", &mut format_index, &all_formats, |format| {
+                format!("{}", format).into()
+            }) {
+                form.format = all_formats[format_index];
+            }
+
+            if form.format.run_format().is_some() {
+                ui.same_line();
+                let all_compressions = Compression::all();
+                let mut compression_index = all_compressions
+                    .iter()
+                    .position(|compression| form.compression == *compression)
+                    .unwrap();
+                ui.set_next_item_width(150.0);
+                if ui.combo(
+                    "##compression",
+                    &mut compression_index,
+                    &all_compressions,
+                    |compression| format!("{}", compression).into(),
+                ) {
+                    form.compression = all_compressions[compression_index];
+                }
+            }
+
+            let Some(estimate) = form.estimate() else {
+                return;
+            };
+
+            ui.spacing();
+            let compression_note =
+                if form.format.run_format().is_some() && form.compression != Compression::None {
+                    " before compression"
+                } else {
+                    ""
+                };
+            match estimate.bytes {
+                Some(bytes) => ui.text(format!(
+                    "{} floats, about {}{}",
+                    estimate.count,
+                    format_size(bytes),
+                    compression_note,
+                )),
+                None => ui.text(format!(
+                    "{} floats, at most {}{}",
+                    estimate.count,
+                    format_size(estimate.max_bytes),
+                    compression_note,
+                )),
+            }
+
+            let enough_space = fits_on_disk(estimate.bytes, form.available_space);
+            if let Some(available) = form.available_space {
+                if !enough_space {
+                    ui.text_colored(
+                        [1.0, 0.3, 0.3, 1.0],
+                        format!("Not enough disk space ({} free)", format_size(available)),
+                    );
+                } else if estimate.max_bytes > available {
+                    ui.text_colored(
+                        [1.0, 0.8, 0.3, 1.0],
+                        format!(
+                            "Warning: the file may not fit on disk ({} free)",
+                            format_size(available),
+                        ),
+                    );
+                }
+            }
+
+            if let (Some(options), Some(filename), true, false) = (
+                form.options(),
+                form.filename.as_ref(),
+                enough_space,
+                exporting,
+            ) {
                 (0..3).for_each(|_| ui.spacing());
                 if ui.button("Export") {
-                    begun = true;
-
-                    let mut writer = BufWriter::new(File::create(filename).unwrap());
-                    let seam = form.seam.clone();
-                    let point_filter = form.point_filter;
-                    let status_filter = form.status_filter;
-                    let include_small_w = form.include_small_w;
-                    let w_range = RangeF32::inclusive(min_w, max_w);
-
-                    thread::spawn(move || {
-                        save_seam_to_csv(
-                            &mut writer,
-                            |progress| {
-                                if let Ok(mut progress_cell) = progress_cell.try_lock() {
-                                    *progress_cell = progress
-                                }
+                    // The free space may have changed since the form was opened. If the export no
+                    // longer fits, the error is shown on the next frame instead.
+                    form.available_space = available_space(filename);
+                    if fits_on_disk(estimate.bytes, form.available_space) {
+                        view.settings.export = ExportDefaults {
+                            filename: filename.clone(),
+                            status_filter: form.status_filter,
+                            format: form.format,
+                            compression: form.compression,
+                            include_small_w: form.include_small_w,
+                        };
+                        let job = ExportJob {
+                            seam: form.seam.clone(),
+                            format: form.format,
+                            compression: form.compression,
+                            options,
+                        };
+                        let filename = filename.clone();
+                        form.export_thread = Some(spawn_export(
+                            &progress_cell,
+                            &cancel,
+                            filename.clone(),
+                            move |set_progress, cancel| {
+                                export_to_file(&filename, &job, set_progress, cancel)
                             },
-                            &seam,
-                            point_filter,
-                            status_filter,
-                            include_small_w,
-                            w_range,
-                        )
-                        .unwrap();
-                    });
+                        ));
+                    }
                 }
             }
         });
 
-    if !opened || begun {
+    if !opened {
         view.export_form = None;
     }
 
     style_token.pop();
 }
+
+fn spawn_export(
+    progress_cell: &Arc<Mutex<Option<ExportProgress>>>,
+    cancel: &Arc<AtomicBool>,
+    filename: String,
+    export: impl FnOnce(&mut dyn FnMut(Option<ExportProgress>), &AtomicBool) -> io::Result<()>
+    + Send
+    + 'static,
+) -> JoinHandle<()> {
+    let progress_cell = Arc::clone(progress_cell);
+    let cancel = Arc::clone(cancel);
+    cancel.store(false, Ordering::Relaxed);
+
+    thread::spawn(move || {
+        let mut set_progress = |progress| {
+            if let Ok(mut progress_cell) = progress_cell.try_lock() {
+                *progress_cell = progress
+            }
+        };
+
+        match export(&mut set_progress, &cancel) {
+            Ok(()) => log::info!("Exported {}", filename),
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => {
+                log::info!("Export of {} cancelled", filename)
+            }
+            Err(error) => log::error!("Failed to export {}: {}", filename, error),
+        }
+        *progress_cell.lock().unwrap() = None;
+    })
+}
+
+/// True unless the estimated size is known to exceed the available space.
+fn fits_on_disk(bytes: Option<u64>, available: Option<u64>) -> bool {
+    match (bytes, available) {
+        (Some(bytes), Some(available)) => bytes <= available,
+        _ => true,
+    }
+}
+
+fn render_scripts_window(ui: &Ui, view: &mut ConnectedView) {
+    let style_token = ui.push_style_color(imgui::StyleColor::WindowBg, [0.06, 0.06, 0.06, 0.94]);
+
+    let mut opened = true;
+    ui.window("Scripts")
+        .size([500.0, 400.0], Condition::Appearing)
+        .opened(&mut opened)
+        .build(|| {
+            let scripts = &mut view.scripts;
+            let job_running = scripts.is_job_running();
+
+            ui.text(format!(
+                "Scripts are loaded from the \"{}\" folder",
+                SCRIPT_DIR
+            ));
+            if ui.button("Reload") {
+                scripts.reload();
+                if let PointFilter::Custom(_) = view.seam_processor.filter() {
+                    view.seam_processor.set_filter(PointFilter::None);
+                }
+                for seam_view in &mut view.seam_views {
+                    if let PointFilter::Custom(_) = seam_view.filter {
+                        seam_view.filter = PointFilter::None;
+                    }
+                }
+                if let Some(form) = &mut view.export_form
+                    && let PointFilter::Custom(_) = form.point_filter
+                {
+                    form.point_filter = PointFilter::None;
+                }
+            }
+
+            ui.separator();
+            let mut run_script = None;
+            for (i, script) in scripts.scripts.iter().enumerate() {
+                let hooks = [
+                    (script.has_point_filter, "point_filter"),
+                    (script.has_seam_color, "seam_color"),
+                    (script.has_main, "main"),
+                ]
+                .into_iter()
+                .filter(|(defined, _)| *defined)
+                .map(|(_, name)| name)
+                .join(", ");
+                ui.text(format!("{}: {}", script.name, hooks));
+
+                if script.has_main && !job_running {
+                    ui.same_line();
+                    if ui.button(format!("Run##{}", i)) {
+                        run_script = Some(i);
+                    }
+                }
+            }
+            if let Some(i) = run_script {
+                scripts.run_main(i);
+            }
+
+            let coloring_scripts: Vec<Option<usize>> = iter::once(None)
+                .chain(
+                    (0..scripts.scripts.len())
+                        .filter(|&i| scripts.scripts[i].has_seam_color)
+                        .map(Some),
+                )
+                .collect();
+            let mut coloring_index = coloring_scripts
+                .iter()
+                .position(|index| *index == scripts.coloring_script)
+                .unwrap_or(0);
+            ui.spacing();
+            ui.text("Seam colors: ");
+            ui.same_line();
+            ui.set_next_item_width(150.0);
+            if ui.combo(
+                "##seam-colors",
+                &mut coloring_index,
+                &coloring_scripts,
+                |index| match index {
+                    Some(i) => scripts.scripts[*i].name.clone().into(),
+                    None => "default".into(),
+                },
+            ) {
+                scripts.coloring_script = coloring_scripts[coloring_index];
+            }
+
+            for error in &scripts.errors {
+                ui.text_colored([1.0, 0.3, 0.3, 1.0], error);
+            }
+
+            ui.separator();
+            let job = scripts.job.lock().unwrap();
+            if let Some(name) = &job.running {
+                ui.text(format!("Running {}...", name));
+                ui.same_line();
+                if ui.button("Stop") {
+                    scripts.stop_job();
+                }
+            }
+            ui.child_window("script-output").build(|| {
+                for line in &job.output {
+                    ui.text(line);
+                }
+            });
+        });
+
+    if !opened {
+        view.show_scripts = false;
+    }
+
+    style_token.pop();
+}
+
+fn render_area_report_window(ui: &Ui, view: &mut ConnectedView, state: &GameState) {
+    let style_token = ui.push_style_color(imgui::StyleColor::WindowBg, [0.06, 0.06, 0.06, 0.94]);
+
+    let mut opened = true;
+    ui.window("Area report")
+        .size([400.0, 150.0], Condition::Appearing)
+        .opened(&mut opened)
+        .build(|| {
+            ui.text(format!(
+                "Counts the gaps and overlaps on all {} seams using every built-in filter",
+                view.seam_processor.active_seams().len()
+            ));
+            if let PointFilter::Custom(custom) = view.seam_processor.filter() {
+                ui.text(format!("and the selected filter \"{}\".", custom.name()));
+                ui.text_wrapped(
+                    "Custom filters run a script for every float, so the report will be much slower.",
+                );
+            } else {
+                ui.text("Select a custom filter in the world view to include it.");
+            }
+
+            ui.spacing();
+            ui.text("Filename: ");
+            ui.same_line_with_pos(80.0);
+            ui.set_next_item_width(200.0);
+            ui.input_text("##report-name", &mut view.area_report_name)
+                .build();
+            ui.same_line();
+            ui.text(".json/.csv");
+
+            ui.spacing();
+            if let Some(progress) = view.area_report_progress.lock().unwrap().as_ref() {
+                ui.text(format!(
+                    "Generating ({:.1}%)",
+                    progress.complete as f32 / progress.total as f32 * 100.0,
+                ));
+                ui.same_line();
+                if ui.small_button("Cancel") {
+                    view.area_report_cancel.store(true, Ordering::Relaxed);
+                }
+                return;
+            }
+
+            let name = view.area_report_name.trim().to_owned();
+            if !name.is_empty() && ui.button("Export") {
+                let surfaces = state.surfaces.clone();
+                let seams: Vec<_> = view
+                    .seam_processor
+                    .active_seams()
+                    .iter()
+                    .map(|seam| (seam.clone(), view.seam_processor.seam_progress(seam)))
+                    .collect();
+                let segment_filter = view.seam_processor.filter().clone();
+
+                let progress_cell = Arc::clone(&view.area_report_progress);
+                let cancel = Arc::clone(&view.area_report_cancel);
+                cancel.store(false, Ordering::Relaxed);
+                *progress_cell.lock().unwrap() = Some(ExportProgress {
+                    complete: 0,
+                    total: 1,
+                });
+
+                thread::spawn(move || {
+                    let set_progress = |progress| {
+                        if let Ok(mut progress_cell) = progress_cell.try_lock() {
+                            *progress_cell = progress
+                        }
+                    };
+                    let result =
+                        build_area_report(&surfaces, &seams, segment_filter, set_progress, &cancel)
+                            .and_then(|report| save_area_report(&name, &report));
+
+                    match result {
+                        Ok(()) => log::info!("Exported area report {}", name),
+                        Err(error) if error.kind() == io::ErrorKind::Interrupted => {
+                            log::info!("Area report {} cancelled", name)
+                        }
+                        Err(error) => {
+                            log::error!("Failed to export area report {}: {}", name, error)
+                        }
+                    }
+                    *progress_cell.lock().unwrap() = None;
+                });
+            }
+        });
+
+    if !opened {
+        view.show_area_report = false;
+    }
+
+    style_token.pop();
+}
+
+fn render_mesh_export_window(ui: &Ui, view: &mut ConnectedView) {
+    let form = view.mesh_export_form.as_mut().unwrap();
+
+    let style_token = ui.push_style_color(imgui::StyleColor::WindowBg, [0.06, 0.06, 0.06, 0.94]);
+
+    let mut opened = true;
+    ui.window("Export mesh")
+        .size([400.0, 150.0], Condition::Appearing)
+        .opened(&mut opened)
+        .build(|| {
+            ui.text("Saves the surfaces and seams shown in the world view");
+
+            ui.spacing();
+            ui.text("Filename: ");
+            ui.same_line_with_pos(80.0);
+            ui.set_next_item_width(200.0);
+            ui.input_text("##mesh-name", &mut form.name).build();
+
+            ui.same_line();
+            let all_formats = MeshFormat::all();
+            let mut format_index = all_formats
+                .iter()
+                .position(|format| form.format == *format)
+                .unwrap();
+            ui.set_next_item_width(60.0);
+            if ui.combo("##mesh-format", &mut format_index, &all_formats, |format| {
+                format!("{}", format).into()
+            }) {
+                form.format = all_formats[format_index];
+            }
+
+            ui.spacing();
+            if !form.name.trim().is_empty() && ui.button("Export") {
+                form.requested = true;
+            }
+            if let Some(status) = &form.status {
+                ui.text(status);
+            }
+        });
+
+    if !opened {
+        view.mesh_export_form = None;
+    }
+
+    style_token.pop();
+}
+
+fn render_seam_list_window(ui: &Ui, view: &mut ConnectedView) {
+    let style_token = ui.push_style_color(imgui::StyleColor::WindowBg, [0.06, 0.06, 0.06, 0.94]);
+
+    let mut opened = true;
+    ui.window("Seams")
+        .size([560.0, 400.0], Condition::Appearing)
+        .opened(&mut opened)
+        .build(|| {
+            let mut rows: Vec<SeamListRow> = view
+                .seam_processor
+                .active_seams()
+                .iter()
+                .map(|seam| SeamListRow::new(seam, &view.seam_processor))
+                .collect();
+            let total = rows.len();
+
+            ui.set_next_item_width(250.0);
+            ui.input_text("##seam-search", &mut view.seam_list_search)
+                .hint("search text or x z / x y z")
+                .build();
+            rows.retain(|row| row.matches(&view.seam_list_search));
+            ui.same_line();
+            ui.text(format!("{} / {} seams", rows.len(), total));
+
+            let columns = SeamListColumn::all();
+            let flags = imgui::TableFlags::SORTABLE
+                | imgui::TableFlags::SORT_TRISTATE
+                | imgui::TableFlags::RESIZABLE
+                | imgui::TableFlags::ROW_BG
+                | imgui::TableFlags::BORDERS_OUTER
+                | imgui::TableFlags::SCROLL_Y;
+            let Some(_table) =
+                ui.begin_table_with_sizing("##seam-list", columns.len(), flags, [0.0, 0.0], 0.0)
+            else {
+                return;
+            };
+            ui.table_setup_scroll_freeze(0, 1);
+            for column in &columns {
+                ui.table_setup_column(column.name());
+            }
+            ui.table_headers_row();
+
+            // Progress changes every frame, so sort every frame instead of only when the sort
+            // specs change
+            if let Some(sort_specs) = ui.table_sort_specs_mut()
+                && let Some(spec) = sort_specs.specs().iter().next()
+            {
+                let column = columns[spec.column_idx()];
+                let descending =
+                    spec.sort_direction() == Some(imgui::TableSortDirection::Descending);
+                rows.sort_by(|a, b| {
+                    let ordering = a.compare(b, column);
+                    if descending {
+                        ordering.reverse()
+                    } else {
+                        ordering
+                    }
+                });
+            }
+
+            let selected_seam = view.seam_view().map(|seam_view| &seam_view.seam);
+            let mut clicked_seam = None;
+            for (index, row) in rows.iter().enumerate() {
+                let (endpoint1, endpoint2) = row.seam.endpoints;
+
+                ui.table_next_row();
+                ui.table_next_column();
+                if ui
+                    .selectable_config(format!(
+                        "{}##seam-{}",
+                        SeamListRow::endpoint_text(endpoint1),
+                        index
+                    ))
+                    .selected(selected_seam == Some(&row.seam))
+                    .span_all_columns(true)
+                    .build()
+                {
+                    clicked_seam = Some(row.seam.clone());
+                }
+                ui.table_next_column();
+                ui.text(SeamListRow::endpoint_text(endpoint2));
+                ui.table_next_column();
+                ui.text(row.axis.to_string());
+                ui.table_next_column();
+                ui.text(format!("{:.1}", row.length));
+                ui.table_next_column();
+                ui.text(row.progress_text());
+                ui.table_next_column();
+                if row.has_gap {
+                    ui.text("gap");
+                }
+                ui.table_next_column();
+                if row.has_overlap {
+                    ui.text("overlap");
+                }
+            }
+
+            if let Some(seam) = clicked_seam {
+                view.open_seam_view(seam, ui.io().key_ctrl);
+            }
+        });
+
+    if !opened {
+        view.show_seam_list = false;
+    }
+
+    style_token.pop();
+}
+
+fn render_log_window(ui: &Ui, view: &mut ConnectedView, state: &GameState) {
+    let style_token = ui.push_style_color(imgui::StyleColor::WindowBg, [0.06, 0.06, 0.06, 0.94]);
+
+    let mut opened = true;
+    ui.window("Log")
+        .size([600.0, 300.0], Condition::Appearing)
+        .opened(&mut opened)
+        .build(|| {
+            let records: Vec<_> = recent_records()
+                .into_iter()
+                .filter(|record| record.level <= view.log_level)
+                .collect();
+
+            let all_levels = [Level::Error, Level::Warn, Level::Info];
+            let mut level_index = all_levels
+                .iter()
+                .position(|level| *level == view.log_level)
+                .unwrap_or(all_levels.len() - 1);
+            ui.text("Level: ");
+            ui.same_line();
+            ui.set_next_item_width(80.0);
+            if ui.combo("##log-level", &mut level_index, &all_levels, |level| {
+                format!("{}", level).into()
+            }) {
+                view.log_level = all_levels[level_index];
+            }
+
+            ui.same_line();
+            if ui.button("Clear") {
+                clear_recent_records();
+            }
+            ui.same_line();
+            if ui.button("Copy") {
+                ui.set_clipboard_text(records.iter().map(|record| record.line()).join("\n"));
+            }
+            ui.same_line();
+            if ui.button("Create diagnostic bundle") {
+                let surface_pool = read_surface_pool_bytes(&view.globals, &view.process);
+                let result = create_diagnostic_bundle(
+                    &view.settings,
+                    &view.globals,
+                    &surface_pool,
+                    &state.surfaces,
+                );
+                view.diagnostic_bundle_status = Some(match result {
+                    Ok(filename) => {
+                        log::info!("Created diagnostic bundle {}", filename);
+                        format!("Saved {}", filename)
+                    }
+                    Err(error) => {
+                        log::error!("Failed to create diagnostic bundle: {}", error);
+                        format!("Failed to create diagnostic bundle: {}", error)
+                    }
+                });
+            }
+            if let Some(status) = &view.diagnostic_bundle_status {
+                ui.text(status);
+            }
+            ui.text(format!("The full log is saved to {}", LOG_FILE));
+
+            ui.separator();
+            ui.child_window("log-records").build(|| {
+                for record in &records {
+                    match record.level {
+                        Level::Error => ui.text_colored([1.0, 0.3, 0.3, 1.0], record.line()),
+                        Level::Warn => ui.text_colored([1.0, 0.8, 0.3, 1.0], record.line()),
+                        _ => ui.text(record.line()),
+                    }
+                }
+                // Follow new records unless the user has scrolled up
+                if ui.scroll_y() >= ui.scroll_max_y() {
+                    ui.set_scroll_here_y_with_ratio(1.0);
+                }
+            });
+        });
+
+    if !opened {
+        view.show_log = false;
+    }
+
+    style_token.pop();
+}
+
+fn render_bookmarks_window(ui: &Ui, view: &mut ConnectedView) {
+    let style_token = ui.push_style_color(imgui::StyleColor::WindowBg, [0.06, 0.06, 0.06, 0.94]);
+
+    let mut opened = true;
+    ui.window("Bookmarks")
+        .size([560.0, 300.0], Condition::Appearing)
+        .opened(&mut opened)
+        .build(|| {
+            if let Some(error) = &view.bookmarks_error {
+                ui.text_colored([1.0, 0.3, 0.3, 1.0], error);
+            }
+            if view.bookmarks.seams.is_empty() {
+                ui.text("Use the \"Bookmark\" button in the seam view to add a bookmark");
+                return;
+            }
+
+            let flags = imgui::TableFlags::RESIZABLE
+                | imgui::TableFlags::ROW_BG
+                | imgui::TableFlags::BORDERS_OUTER
+                | imgui::TableFlags::SCROLL_Y;
+            let Some(_table) = ui.begin_table_with_sizing("##bookmarks", 5, flags, [0.0, 0.0], 0.0)
+            else {
+                return;
+            };
+            ui.table_setup_scroll_freeze(0, 1);
+            for name in ["Name", "Comment", "Seam", "w, y", ""] {
+                ui.table_setup_column(name);
+            }
+            ui.table_headers_row();
+
+            let active_seams = view.seam_processor.active_seams();
+            let mut jump_to = None;
+            let mut remove = None;
+            for (seam_index, entry) in view.bookmarks.seams.iter().enumerate() {
+                let is_active = active_seams.contains(&entry.seam);
+                for (index, bookmark) in entry.bookmarks.iter().enumerate() {
+                    let _id = ui.push_id(format!("{}-{}", seam_index, index));
+
+                    ui.table_next_row();
+                    ui.table_next_column();
+                    ui.text(&bookmark.name);
+                    ui.table_next_column();
+                    ui.text(&bookmark.comment);
+                    ui.table_next_column();
+                    let seam_text = format!(
+                        "{} - {}",
+                        SeamListRow::endpoint_text(entry.seam.endpoints.0),
+                        SeamListRow::endpoint_text(entry.seam.endpoints.1)
+                    );
+                    if is_active {
+                        ui.text(seam_text);
+                    } else {
+                        ui.text_disabled(seam_text);
+                    }
+                    ui.table_next_column();
+                    ui.text(format!("{}, {}", bookmark.w, bookmark.y));
+                    ui.table_next_column();
+                    if ui.small_button("Go") {
+                        jump_to = Some((
+                            entry.seam.clone(),
+                            ProjectedPoint {
+                                w: bookmark.w,
+                                y: bookmark.y,
+                            },
+                        ));
+                    }
+                    ui.same_line();
+                    if ui.small_button("Delete") {
+                        remove = Some((entry.seam.clone(), index));
+                    }
+                }
+            }
+
+            if let Some((seam, point)) = jump_to {
+                view.open_seam_view(seam, ui.io().key_ctrl).pending_jump = Some(point);
+            }
+            if let Some((seam, index)) = remove {
+                view.bookmarks.remove(&seam, index);
+                view.bookmarks_error = save_bookmarks(&view.bookmarks);
+            }
+        });
+
+    if !opened {
+        view.show_bookmarks = false;
+    }
+
+    style_token.pop();
+}
+
+fn render_surface_inspector(ui: &Ui, view: &mut ConnectedView, state: &GameState) {
+    let surface_index = view.inspected_surface.unwrap();
+
+    let style_token = ui.push_style_color(imgui::StyleColor::WindowBg, [0.06, 0.06, 0.06, 0.94]);
+
+    let mut opened = true;
+    ui.window("Surface")
+        .size([350.0, 300.0], Condition::Appearing)
+        .opened(&mut opened)
+        .build(|| {
+            let Some(surface) = state.surfaces.get(surface_index) else {
+                ui.text(format!("Surface {} is no longer loaded", surface_index));
+                return;
+            };
+
+            ui.text(format!("Index: {}", surface_index));
+            ui.text(format!("Type: {:#06X}", surface.surface_type));
+            ui.text(format!("Flags: {:#04X}", surface.flags));
+            ui.text(format!("Room: {}", surface.room));
+            if surface.object == 0 {
+                ui.text("Object: none");
+            } else {
+                ui.text(format!("Object: {:#010X}", surface.object));
+            }
+
+            ui.spacing();
+            for (i, vertex) in [surface.vertex1, surface.vertex2, surface.vertex3]
+                .iter()
+                .enumerate()
+            {
+                ui.text(format!(
+                    "Vertex {}: ({}, {}, {})",
+                    i + 1,
+                    vertex[0],
+                    vertex[1],
+                    vertex[2]
+                ));
+            }
+            ui.text(format!(
+                "Normal: ({}, {}, {})",
+                surface.normal[0], surface.normal[1], surface.normal[2]
+            ));
+            ui.text(format!("Origin offset: {}", surface.origin_offset));
+
+            ui.spacing();
+            if ui.small_button("Hide") {
+                view.surface_visibility.hide(surface);
+            }
+            ui.same_line();
+            if ui.small_button("Isolate room") {
+                view.surface_visibility.isolated_room = Some(surface.room);
+            }
+
+            ui.spacing();
+            let seams = find_surface_seams(surface, view.seam_processor.active_seams());
+            if seams.is_empty() {
+                ui.text("No seams");
+            } else {
+                ui.text("Seams:");
+            }
+            for (i, seam) in seams.into_iter().enumerate() {
+                if ui.small_button(format!("Open##surface-seam-{}", i)) {
+                    view.open_seam_view(seam.clone(), ui.io().key_ctrl);
+                }
+                ui.same_line();
+                ui.text(format!(
+                    "{} - {}",
+                    SeamListRow::endpoint_text(seam.endpoints.0),
+                    SeamListRow::endpoint_text(seam.endpoints.1)
+                ));
+            }
+        });
+
+    if !opened {
+        view.inspected_surface = None;
+    }
+
+    style_token.pop();
+}
+
+fn render_surface_visibility_window(ui: &Ui, view: &mut ConnectedView, state: &GameState) {
+    let visibility = &mut view.surface_visibility;
+
+    let style_token = ui.push_style_color(imgui::StyleColor::WindowBg, [0.06, 0.06, 0.06, 0.94]);
+
+    let mut opened = true;
+    ui.window("Surfaces")
+        .size([350.0, 220.0], Condition::Appearing)
+        .opened(&mut opened)
+        .build(|| {
+            ui.text("Hide:");
+            ui.same_line();
+            ui.checkbox("floors", &mut visibility.hide_floors);
+            ui.same_line();
+            ui.checkbox("ceilings", &mut visibility.hide_ceilings);
+            ui.same_line();
+            ui.checkbox("walls", &mut visibility.hide_walls);
+
+            ui.spacing();
+            ui.text(format!(
+                "{} surfaces hidden by clicking",
+                visibility.hidden.len()
+            ));
+            if !visibility.hidden.is_empty() {
+                ui.same_line();
+                if ui.small_button("Show all") {
+                    visibility.hidden.clear();
+                }
+            }
+
+            ui.spacing();
+            let mut isolate_room = visibility.isolated_room.is_some();
+            if ui.checkbox("Isolate room", &mut isolate_room) {
+                visibility.isolated_room = isolate_room.then(|| {
+                    view.inspected_surface
+                        .and_then(|index| state.surfaces.get(index))
+                        .map(|surface| surface.room)
+                        .unwrap_or(0)
+                });
+            }
+            if let Some(room) = &mut visibility.isolated_room {
+                ui.same_line();
+                let mut room_value = *room as i32;
+                ui.set_next_item_width(80.0);
+                if ui.input_int("##isolated-room", &mut room_value).build() {
+                    *room = room_value.clamp(i8::MIN as i32, i8::MAX as i32) as i8;
+                }
+            }
+
+            let inspected_surface = view
+                .inspected_surface
+                .and_then(|index| state.surfaces.get(index));
+            let mut isolate_region = visibility.isolated_region.is_some();
+            if ui.checkbox("Isolate region", &mut isolate_region) {
+                visibility.isolated_region = if isolate_region {
+                    let center = match inspected_surface {
+                        Some(surface) => surface.vertices().iter().fold([0.0; 3], |sum, v| {
+                            [sum[0] + v.x / 3.0, sum[1] + v.y / 3.0, sum[2] + v.z / 3.0]
+                        }),
+                        None => state.lakitu_focus,
+                    };
+                    Some((center, DEFAULT_ISOLATED_REGION_RADIUS))
+                } else {
+                    None
+                };
+            }
+            if let Some((center, radius)) = &mut visibility.isolated_region {
+                ui.same_line();
+                ui.set_next_item_width(80.0);
+                ui.input_float("radius", radius).build();
+                ui.text(format!(
+                    "    around ({:.0}, {:.0}, {:.0})",
+                    center[0], center[1], center[2]
+                ));
+            }
+            ui.text_disabled("Regions are centered on the inspected surface, or the camera focus");
+        });
+
+    if !opened {
+        view.show_surface_visibility = false;
+    }
+
+    style_token.pop();
+}
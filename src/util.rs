@@ -3,25 +3,45 @@ use crate::{
     float_range::RangeF32,
     float_range::next_f32,
     float_range::prev_f32,
-    game_state::{GameState, Globals},
+    game_state::{GameState, Globals, Surface},
     geo::{Point3f, Vector3f, direction_to_pitch_yaw, pitch_yaw_to_direction},
-    graphics::{self, Camera, GameViewScene, RotateCamera, SurfaceType, Viewport},
-    model::ExportProgress,
+    graphics::{self, BirdsEyeCamera, Camera, GameViewScene, RotateCamera, SurfaceType, Viewport},
+    palette::Palette,
     process::Process,
-    seam::PointFilter,
-    seam::PointStatusFilter,
-    seam::Seam,
+    seam::{PointFilter, PointStatus, Seam},
     seam_processor::{SeamOutput, SeamProcessor, SeamProgress},
 };
 use graphics::{FocusedSeamData, FocusedSeamInfo, SeamInfo, SeamSegment, SeamViewCamera};
+use nalgebra::Vector2;
+use rayon::{ThreadPool, ThreadPoolBuilder, prelude::*};
 use std::{
     collections::HashSet,
     f32::consts::PI,
-    io,
-    io::Write,
+    iter,
+    sync::{
+        OnceLock,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
     time::{Duration, Instant},
 };
 
+const POINT_SEARCH_CHUNK_SIZE: usize = 1 << 16;
+
+/// Histograms are counted on their own small thread pool so that they don't starve the seam
+/// processor, which uses the global pool.
+fn histogram_pool() -> &'static ThreadPool {
+    static POOL: OnceLock<ThreadPool> = OnceLock::new();
+    POOL.get_or_init(|| {
+        let num_threads = thread::available_parallelism().map_or(1, |n| (n.get() / 4).max(1));
+        ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .thread_name(|index| format!("histogram-{}", index))
+            .build()
+            .expect("failed to create histogram thread pool")
+    })
+}
+
 pub fn get_norm_mouse_pos(
     mouse_pos: [f32; 2],
     window_pos: [f32; 2],
@@ -63,18 +83,47 @@ pub fn get_mouse_ray(
     Some((camera.pos(), mouse_dir))
 }
 
+/// Return a ray pointing straight down from the point under the mouse.
+pub fn get_birds_eye_mouse_ray(
+    mouse_pos: [f32; 2],
+    window_pos: [f32; 2],
+    window_size: [f32; 2],
+    camera: &BirdsEyeCamera,
+) -> Option<(Point3f, Vector3f)> {
+    let norm_mouse_pos = get_norm_mouse_pos(mouse_pos, window_pos, window_size);
+    if norm_mouse_pos.0.abs() > 1.0 || norm_mouse_pos.1.abs() > 1.0 {
+        return None;
+    }
+
+    // World x is screen up and world z is screen right
+    let span_x = camera.span_y * window_size[0] / window_size[1];
+    let origin = Point3f::new(
+        camera.pos[0] + norm_mouse_pos.1 * camera.span_y / 2.0,
+        camera.pos[1],
+        camera.pos[2] + norm_mouse_pos.0 * span_x / 2.0,
+    );
+
+    Some((origin, -Vector3f::y()))
+}
+
+/// Find the nearest surface hit by the ray, ignoring surfaces in `hidden_surfaces`.
 pub fn ray_surface_intersection(
     state: &GameState,
+    hidden_surfaces: &HashSet<usize>,
     ray: (Point3f, Vector3f),
 ) -> Option<(usize, Point3f)> {
     let mut nearest: Option<(f32, (usize, Point3f))> = None;
 
     for (i, surface) in state.surfaces.iter().enumerate() {
+        if hidden_surfaces.contains(&i) {
+            continue;
+        }
+
         let normal = surface.normal();
         let vertices = surface.vertices();
 
         let t = -normal.dot(&(ray.0 - vertices[0])) / normal.dot(&ray.1);
-        if t <= 0.0 {
+        if t <= 0.0 || !t.is_finite() {
             continue;
         }
 
@@ -102,10 +151,11 @@ pub fn ray_surface_intersection(
 
 pub fn find_hovered_seam(
     state: &GameState,
+    hidden_surfaces: &HashSet<usize>,
     active_seams: &[Seam],
     mouse_ray: (Point3f, Vector3f),
 ) -> Option<Seam> {
-    let (surface_index, point) = ray_surface_intersection(state, mouse_ray)?;
+    let (surface_index, point) = ray_surface_intersection(state, hidden_surfaces, mouse_ray)?;
     let surface = &state.surfaces[surface_index];
     let vertices = [surface.vertex1, surface.vertex2, surface.vertex3];
 
@@ -130,19 +180,64 @@ pub fn find_hovered_seam(
         .cloned()
 }
 
+/// Find the seam closest to the point (x, z) when viewed from above, if any are within
+/// `max_distance`.
+pub fn find_nearest_seam_from_above(
+    active_seams: &[Seam],
+    x: f32,
+    z: f32,
+    max_distance: f32,
+) -> Option<Seam> {
+    let point = Vector2::new(x, z);
+    active_seams
+        .iter()
+        .map(|seam| {
+            let (endpoint1, endpoint2) = seam.endpoints;
+            let a = Vector2::new(endpoint1[0] as f32, endpoint1[2] as f32);
+            let b = Vector2::new(endpoint2[0] as f32, endpoint2[2] as f32);
+            let t = if a == b {
+                0.0
+            } else {
+                ((point - a).dot(&(b - a)) / (b - a).norm_squared()).clamp(0.0, 1.0)
+            };
+            ((a + t * (b - a) - point).norm(), seam)
+        })
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by(|(distance1, _), (distance2, _)| distance1.total_cmp(distance2))
+        .map(|(_, seam)| seam.clone())
+}
+
+/// The seams that have an edge of the given surface as one of their edges.
+pub fn find_surface_seams(surface: &Surface, active_seams: &[Seam]) -> Vec<Seam> {
+    // Only walls have seams
+    if surface.normal[1].abs() > 0.01 {
+        return Vec::new();
+    }
+
+    let vertices = [surface.vertex1, surface.vertex2, surface.vertex3];
+    let edges: Vec<Edge> = (0..3)
+        .map(|k| Edge::new((vertices[k], vertices[(k + 1) % 3]), surface.normal))
+        .collect();
+
+    active_seams
+        .iter()
+        .filter(|seam| edges.contains(&seam.edge1) || edges.contains(&seam.edge2))
+        .cloned()
+        .collect()
+}
+
 pub fn build_game_view_scene(
     viewport: Viewport,
+    camera: Camera,
     game_state: &GameState,
     seam_processor: &SeamProcessor,
     hovered_seam: Option<Seam>,
+    selected_seam: Option<Seam>,
+    hidden_surfaces: HashSet<usize>,
 ) -> GameViewScene {
     GameViewScene {
         viewport,
-        camera: Camera::Rotate(RotateCamera {
-            pos: game_state.lakitu_pos,
-            target: game_state.lakitu_focus,
-            fov_y: 45.0,
-        }),
+        camera,
         surfaces: game_state
             .surfaces
             .iter()
@@ -173,7 +268,7 @@ pub fn build_game_view_scene(
             .collect(),
         wall_hitbox_radius: 0.0,
         hovered_surface: None,
-        hidden_surfaces: HashSet::new(),
+        hidden_surfaces,
         seams: seam_processor
             .active_seams()
             .iter()
@@ -183,6 +278,11 @@ pub fn build_game_view_scene(
             })
             .collect(),
         hovered_seam,
+        selected_seam,
+        bookmarks: Vec::new(),
+        palette: Palette::default(),
+        mario: None,
+        mario_trail: Vec::new(),
     }
 }
 
@@ -204,6 +304,7 @@ pub fn get_segment_info(seam: &Seam, progress: &SeamProgress) -> SeamInfo {
                     y: endpoint2[1],
                 },
                 status,
+                custom_color: None,
             }
         })
         .collect();
@@ -286,6 +387,135 @@ pub fn get_visible_w_range_for_seam(
     seam.w_range().intersect(&h_range).intersect(&v_range)
 }
 
+/// Find the nearest gap or overlap on the seam after `from_w` (or before it if `forward` is
+/// false), skipping the range [-1, 1].
+///
+/// Returns None if there is no such point or if `cancel` is set.
+pub fn find_next_point(
+    seam: &Seam,
+    from_w: f32,
+    forward: bool,
+    filter: &PointFilter,
+    cancel: &AtomicBool,
+) -> Option<(ProjectedPoint<f32>, PointStatus)> {
+    let search_range = if forward {
+        RangeF32::inclusive_exclusive(next_f32(from_w), seam.w_range().end)
+    } else {
+        RangeF32::inclusive_exclusive(seam.w_range().start, from_w)
+    }
+    .intersect(&seam.w_range());
+
+    let mut chunks = split_into_chunks(search_range);
+    if !forward {
+        chunks.reverse();
+    }
+
+    chunks.par_iter().find_map_first(|chunk| {
+        if cancel.load(Ordering::Relaxed) {
+            return None;
+        }
+        let check = |w: f32| {
+            let (y, status) = seam.check_point(w, filter);
+            (status != PointStatus::None).then_some((ProjectedPoint { w, y }, status))
+        };
+        if forward {
+            chunk.iter().find_map(check)
+        } else {
+            iter::successors(Some(prev_f32(chunk.end)), |w| Some(prev_f32(*w)))
+                .take_while(|w| *w >= chunk.start)
+                .find_map(check)
+        }
+    })
+}
+
+/// Split a range of w values into chunks that can be checked in parallel, leaving out the values
+/// between -1 and 1.
+pub fn split_into_chunks(w_range: RangeF32) -> Vec<RangeF32> {
+    let (left_range, right_range) = w_range.cut_out(&RangeF32::inclusive_exclusive(-1.0, 1.0));
+
+    let mut chunks = Vec::new();
+    for range in [left_range, right_range] {
+        let mut start = range.start;
+        while start < range.end {
+            let end = RangeF32::inclusive_exclusive(start, range.end)
+                .nth(POINT_SEARCH_CHUNK_SIZE)
+                .unwrap_or(range.end);
+            chunks.push(RangeF32::inclusive_exclusive(start, end));
+            start = end;
+        }
+    }
+    chunks
+}
+
+/// Divide a w range into buckets of equal width.
+pub fn histogram_buckets(w_range: RangeF32, num_buckets: usize) -> Vec<RangeF32> {
+    let boundary = |i: usize| {
+        if i == num_buckets {
+            w_range.end
+        } else {
+            (w_range.start as f64
+                + (w_range.end as f64 - w_range.start as f64) * i as f64 / num_buckets as f64)
+                as f32
+        }
+    };
+    (0..num_buckets)
+        .map(|i| RangeF32::inclusive_exclusive(boundary(i), boundary(i + 1)))
+        .collect()
+}
+
+/// Count the gaps and overlaps in each bucket, calling `on_bucket` with the bucket's index and
+/// `(num_gaps, num_overlaps)` as each one finishes.
+///
+/// Runs on the histogram thread pool, blocking until every bucket is counted.
+pub fn count_points_in_buckets(
+    seam: &Seam,
+    buckets: &[RangeF32],
+    filter: &PointFilter,
+    cancel: &AtomicBool,
+    on_bucket: impl Fn(usize, (usize, usize)) + Sync,
+) {
+    histogram_pool().install(|| {
+        buckets.par_iter().enumerate().for_each(|(index, bucket)| {
+            let counts = split_into_chunks(*bucket)
+                .par_iter()
+                .map(|chunk| {
+                    if cancel.load(Ordering::Relaxed) {
+                        return (0, 0);
+                    }
+                    seam.count_range(*chunk, filter)
+                })
+                .reduce(|| (0, 0), |a, b| (a.0 + b.0, a.1 + b.1));
+            if !cancel.load(Ordering::Relaxed) {
+                on_bucket(index, counts);
+            }
+        })
+    });
+}
+
+/// The approximate distance from a point to the nearest point on a seam.
+pub fn distance_to_seam(seam: &Seam, pos: Point3f) -> f32 {
+    let w_range = seam.w_range();
+    let w = ProjectedPoint::project([pos.x, pos.y, pos.z], seam.edge1.projection_axis)
+        .w
+        .clamp(w_range.start, w_range.end);
+    (pos - Point3f::from(seam.approx_point_at_w(w))).norm()
+}
+
+/// The horizontal direction for an angle in the game's units, where 0 faces +z.
+pub fn yaw_to_direction(yaw: i16) -> Vector3f {
+    let angle = yaw as f32 / 65536.0 * 2.0 * PI;
+    Vector3f::new(angle.sin(), 0.0, angle.cos())
+}
+
+/// Parse a float given either in decimal or as hex bits, e.g. `0x44800000`.
+pub fn parse_f32(text: &str) -> Option<f32> {
+    let text = text.trim();
+    match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(hex) => u32::from_str_radix(hex, 16).ok().map(f32::from_bits),
+        None => text.parse().ok(),
+    }
+}
+
 pub fn canonicalize_process_name(name: &str) -> String {
     name.trim_end_matches(".exe")
         .replace("_", "-")
@@ -302,56 +532,3 @@ pub fn sync_to_game(process: &Process, globals: &Globals) {
         }
     }
 }
-
-pub fn save_seam_to_csv(
-    writer: &mut impl Write,
-    mut set_progress: impl FnMut(Option<ExportProgress>),
-    seam: &Seam,
-    point_filter: PointFilter,
-    status_filter: PointStatusFilter,
-    include_small_w: bool,
-    w_range: RangeF32,
-) -> io::Result<()> {
-    match seam.edge1.projection_axis {
-        ProjectionAxis::X => writeln!(writer, "z,z hex,y,y hex,type")?,
-        ProjectionAxis::Z => writeln!(writer, "x,x hex,y,y hex,type")?,
-    }
-
-    let w_ranges = if include_small_w {
-        vec![w_range]
-    } else {
-        let (left, right) = w_range.cut_out(&RangeF32::inclusive_exclusive(-1.0, 1.0));
-        vec![left, right]
-    };
-
-    let total = w_ranges.iter().map(|range| range.count()).sum();
-    let mut complete = 0;
-
-    for w in w_ranges
-        .into_iter()
-        .flat_map(|range| range.iter().collect::<Vec<_>>())
-    {
-        let (y, status) = seam.check_point(w, point_filter);
-        complete += 1;
-
-        if complete % 100_000 == 0 {
-            set_progress(Some(ExportProgress { complete, total }));
-        }
-
-        if status_filter.matches(status) {
-            writeln!(
-                writer,
-                "{},{:#08X},{},{:#08X},{}",
-                w,
-                w.to_bits(),
-                y,
-                y.to_bits(),
-                status,
-            )?;
-        }
-    }
-
-    writer.flush()?;
-    set_progress(None);
-    Ok(())
-}